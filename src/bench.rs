@@ -0,0 +1,174 @@
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+use crate::{input_path, registry, ParseError, Solver, Timing};
+
+/// Fastest, median and slowest duration of one phase over every iteration.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let median = match samples.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 1 => samples[n / 2],
+            n => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+        };
+        Self {
+            min: samples.first().copied().unwrap_or_default(),
+            median,
+            max: samples.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Timings of every phase of one day, over a number of iterations.
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    pub day: u8,
+    /// Name of the bundled input file the day was run on.
+    pub input: String,
+    pub iterations: usize,
+    pub parse: Summary,
+    pub part_one: Summary,
+    pub part_two: Summary,
+}
+
+impl Benchmark {
+    fn phases(&self) -> [(&'static str, &Summary); 3] {
+        [
+            ("parse", &self.parse),
+            ("part_one", &self.part_one),
+            ("part_two", &self.part_two),
+        ]
+    }
+
+    /// Tab-separated rows, one per phase, with durations in nanoseconds.
+    ///
+    /// The columns match [`TSV_HEADER`], so results from runs before and
+    /// after a change can be compared with standard tools.
+    pub fn to_tsv(&self) -> String {
+        self.phases()
+            .iter()
+            .map(|(phase, summary)| {
+                format!(
+                    "{}\t{}\t{}\t{phase}\t{}\t{}\t{}\n",
+                    self.day,
+                    self.input,
+                    self.iterations,
+                    summary.min.as_nanos(),
+                    summary.median.as_nanos(),
+                    summary.max.as_nanos(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Column names for the rows written by [`Benchmark::to_tsv`].
+pub const TSV_HEADER: &str = "day\tinput\titerations\tphase\tmin_ns\tmedian_ns\tmax_ns\n";
+
+/// A table of benchmark timings, one row per day and phase.
+pub struct Table<'a>(pub &'a [Benchmark]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}  {:<8}  {:<8}  {:>12}  {:>12}  {:>12}",
+            "Day", "Input", "Phase", "Min", "Median", "Max"
+        )?;
+        for benchmark in self.0 {
+            for (phase, summary) in benchmark.phases() {
+                write!(
+                    f,
+                    "\n{:>3}  {:<8}  {phase:<8}  {:>12}  {:>12}  {:>12}",
+                    benchmark.day,
+                    benchmark.input,
+                    format!("{:.2?}", summary.min),
+                    format!("{:.2?}", summary.median),
+                    format!("{:.2?}", summary.max),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Times each phase of `solver` on `input` over `iterations` runs.
+pub fn bench(
+    solver: &dyn Solver,
+    input_name: &str,
+    input: &str,
+    iterations: usize,
+) -> Result<Benchmark, ParseError> {
+    let timings = (0..iterations)
+        .map(|_| solver.time(input))
+        .collect::<Result<Vec<Timing>, _>>()?;
+    let summarise =
+        |phase: fn(&Timing) -> Duration| Summary::from_samples(timings.iter().map(phase).collect());
+    Ok(Benchmark {
+        day: solver.day(),
+        input: input_name.to_string(),
+        iterations,
+        parse: summarise(|timing| timing.parse),
+        part_one: summarise(|timing| timing.part_one),
+        part_two: summarise(|timing| timing.part_two),
+    })
+}
+
+/// Benchmarks every registered day, or a single day, on its bundled input
+/// named `input_name`.
+pub fn bench_all(
+    day: Option<u8>,
+    input_name: &str,
+    iterations: usize,
+) -> Vec<Result<Benchmark, String>> {
+    registry()
+        .into_iter()
+        .filter(|(number, _)| day.is_none_or(|day| day == *number))
+        .map(|(number, solver)| {
+            let path = input_path(number, input_name);
+            let input = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read `{}`: {e}", path.display()))?;
+            bench(solver, input_name, &input, iterations).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_6::Day6;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn summarises_odd_number_of_samples() {
+        let summary = Summary::from_samples(millis(&[5, 1, 3]));
+        assert_eq!(summary.min, Duration::from_millis(1));
+        assert_eq!(summary.median, Duration::from_millis(3));
+        assert_eq!(summary.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn summarises_even_number_of_samples() {
+        let summary = Summary::from_samples(millis(&[4, 1, 2, 8]));
+        assert_eq!(summary.median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn writes_one_row_per_phase() {
+        let benchmark = bench(&Day6, "test1", include_str!("day_6/test1"), 3).unwrap();
+        let tsv = benchmark.to_tsv();
+        assert_eq!(tsv.lines().count(), 3);
+        assert!(tsv.starts_with("6\ttest1\t3\tparse\t"));
+    }
+}
@@ -0,0 +1,426 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use aoc_2022::bench::{self, TSV_HEADER};
+use aoc_2022::render::Backend;
+use aoc_2022::report::{self, Format, Record};
+use aoc_2022::verify::{self, Manifest};
+use aoc_2022::{input_path, registry, Part};
+
+const USAGE: &str = "\
+Usage:
+    aoc run --day <DAY> [--part <PART>] [--input <PATH|->] [--format <FORMAT>]
+    aoc verify [--day <DAY>] [--manifest <PATH>]
+    aoc bench [--day <DAY>] [--iterations <N>] [--input <NAME>] [--output <PATH>]
+    aoc report [--day <DAY>] [--input <NAME>] [--format <FORMAT>] [--output <PATH>]
+    aoc render --day <DAY> [--input <PATH|->] [--format <BACKEND>] [--scale <N>] [--output <PATH>]
+
+Commands:
+    run     Solve one day, printing each answer and the time taken
+    verify  Check every solver against a manifest of expected answers
+    bench   Time parsing and each part of every day over many iterations
+    report  Solve both parts of every day, writing one record per part
+    render  Draw a day's state as text, ANSI colour or a PPM/PGM image
+
+Options:
+    -d, --day <DAY>       Day of the advent calendar to solve
+    -p, --part <PART>     Part to solve, `1` or `2` (default: both)
+    -i, --input <PATH|->  Puzzle input file, or `-` for stdin
+                          (default: the day's bundled `input`)
+                          When benchmarking or reporting, the name of a
+                          bundled input
+    -m, --manifest <PATH> Manifest of expected answers
+                          (default: the bundled `src/answers.tsv`)
+    -n, --iterations <N>  Number of times each day is run (default: 10)
+    -f, --format <FORMAT> Write records as `json` lines or `csv`
+                          (default: `json` when reporting, plain text when running)
+                          When rendering, one of `text`, `ansi`, `ppm` or `pgm`
+                          (default: `text`)
+    -s, --scale <N>       Pixels per cell in rendered images (default: 4)
+    -o, --output <PATH>   File to write benchmark results, report records or
+                          rendered frames to (default for reports and frames: stdout)";
+
+/// Where the puzzle input is read from.
+enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// How the source is named in report records.
+    fn name(&self) -> String {
+        match self {
+            Source::File(path) => path.display().to_string(),
+            Source::Stdin => "stdin".to_string(),
+        }
+    }
+
+    fn read(&self) -> Result<String, String> {
+        match self {
+            Source::File(path) => std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read `{}`: {e}", path.display())),
+            Source::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("Failed to read stdin: {e}"))?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+struct RunArgs {
+    day: u8,
+    parts: Vec<Part>,
+    source: Source,
+    format: Option<Format>,
+}
+
+struct VerifyArgs {
+    day: Option<u8>,
+    manifest: Option<PathBuf>,
+}
+
+struct BenchArgs {
+    day: Option<u8>,
+    iterations: usize,
+    input: String,
+    output: Option<PathBuf>,
+}
+
+struct ReportArgs {
+    day: Option<u8>,
+    input: String,
+    format: Format,
+    output: Option<PathBuf>,
+}
+
+struct RenderArgs {
+    day: u8,
+    source: Source,
+    backend: Backend,
+    scale: usize,
+    output: Option<PathBuf>,
+}
+
+enum Command {
+    Run(RunArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    Report(ReportArgs),
+    Render(RenderArgs),
+    Help,
+}
+
+fn value<'a>(flag: &str, args: &mut impl Iterator<Item = &'a String>) -> Result<&'a str, String> {
+    args.next()
+        .map(String::as_str)
+        .ok_or_else(|| format!("Missing value for `{flag}`"))
+}
+
+fn parse_day(raw: &str) -> Result<u8, String> {
+    raw.parse::<u8>()
+        .map_err(|_| format!("Invalid day `{raw}`"))
+}
+
+fn parse_run(args: &[String]) -> Result<RunArgs, String> {
+    let mut day = None;
+    let mut parts = vec![Part::One, Part::Two];
+    let mut source = None;
+    let mut format = None;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-d" | "--day" => day = Some(parse_day(value(flag, &mut args)?)?),
+            "-p" | "--part" => parts = vec![value(flag, &mut args)?.parse::<Part>()?],
+            "-i" | "--input" => {
+                source = Some(match value(flag, &mut args)? {
+                    "-" => Source::Stdin,
+                    path => Source::File(PathBuf::from(path)),
+                })
+            }
+            "-f" | "--format" => format = Some(value(flag, &mut args)?.parse::<Format>()?),
+            other => return Err(format!("Unexpected argument `{other}`")),
+        }
+    }
+
+    let day = day.ok_or("Missing required option `--day`")?;
+    let source = source.unwrap_or_else(|| Source::File(input_path(day, "input")));
+    Ok(RunArgs {
+        day,
+        parts,
+        source,
+        format,
+    })
+}
+
+fn parse_verify(args: &[String]) -> Result<VerifyArgs, String> {
+    let mut verify = VerifyArgs {
+        day: None,
+        manifest: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-d" | "--day" => verify.day = Some(parse_day(value(flag, &mut args)?)?),
+            "-m" | "--manifest" => verify.manifest = Some(PathBuf::from(value(flag, &mut args)?)),
+            other => return Err(format!("Unexpected argument `{other}`")),
+        }
+    }
+    Ok(verify)
+}
+
+fn parse_bench(args: &[String]) -> Result<BenchArgs, String> {
+    let mut bench = BenchArgs {
+        day: None,
+        iterations: 10,
+        input: "input".to_string(),
+        output: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-d" | "--day" => bench.day = Some(parse_day(value(flag, &mut args)?)?),
+            "-n" | "--iterations" => {
+                let raw = value(flag, &mut args)?;
+                bench.iterations = match raw.parse::<usize>() {
+                    Ok(iterations) if iterations > 0 => iterations,
+                    _ => return Err(format!("Invalid number of iterations `{raw}`")),
+                };
+            }
+            "-i" | "--input" => bench.input = value(flag, &mut args)?.to_string(),
+            "-o" | "--output" => bench.output = Some(PathBuf::from(value(flag, &mut args)?)),
+            other => return Err(format!("Unexpected argument `{other}`")),
+        }
+    }
+    Ok(bench)
+}
+
+fn parse_report(args: &[String]) -> Result<ReportArgs, String> {
+    let mut report = ReportArgs {
+        day: None,
+        input: "input".to_string(),
+        format: Format::JsonLines,
+        output: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-d" | "--day" => report.day = Some(parse_day(value(flag, &mut args)?)?),
+            "-i" | "--input" => report.input = value(flag, &mut args)?.to_string(),
+            "-f" | "--format" => report.format = value(flag, &mut args)?.parse::<Format>()?,
+            "-o" | "--output" => report.output = Some(PathBuf::from(value(flag, &mut args)?)),
+            other => return Err(format!("Unexpected argument `{other}`")),
+        }
+    }
+    Ok(report)
+}
+
+fn parse_render(args: &[String]) -> Result<RenderArgs, String> {
+    let mut day = None;
+    let mut source = None;
+    let mut backend = Backend::Text;
+    let mut scale = 4;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-d" | "--day" => day = Some(parse_day(value(flag, &mut args)?)?),
+            "-i" | "--input" => {
+                source = Some(match value(flag, &mut args)? {
+                    "-" => Source::Stdin,
+                    path => Source::File(PathBuf::from(path)),
+                })
+            }
+            "-f" | "--format" => backend = value(flag, &mut args)?.parse::<Backend>()?,
+            "-s" | "--scale" => {
+                let raw = value(flag, &mut args)?;
+                scale = match raw.parse::<usize>() {
+                    Ok(scale) if scale > 0 => scale,
+                    _ => return Err(format!("Invalid scale `{raw}`")),
+                };
+            }
+            "-o" | "--output" => output = Some(PathBuf::from(value(flag, &mut args)?)),
+            other => return Err(format!("Unexpected argument `{other}`")),
+        }
+    }
+
+    let day = day.ok_or("Missing required option `--day`")?;
+    let source = source.unwrap_or_else(|| Source::File(input_path(day, "input")));
+    Ok(RenderArgs {
+        day,
+        source,
+        backend,
+        scale,
+        output,
+    })
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.split_first() {
+        Some((command, rest)) if command == "run" => parse_run(rest).map(Command::Run),
+        Some((command, rest)) if command == "verify" => parse_verify(rest).map(Command::Verify),
+        Some((command, rest)) if command == "bench" => parse_bench(rest).map(Command::Bench),
+        Some((command, rest)) if command == "report" => parse_report(rest).map(Command::Report),
+        Some((command, rest)) if command == "render" => parse_render(rest).map(Command::Render),
+        Some((command, _)) if command == "-h" || command == "--help" || command == "help" => {
+            Ok(Command::Help)
+        }
+        Some((command, _)) => Err(format!("Unknown command `{command}`")),
+        None => Err("No command given".to_string()),
+    }
+}
+
+fn run(
+    RunArgs {
+        day,
+        parts,
+        source,
+        format,
+    }: RunArgs,
+) -> Result<(), String> {
+    let registry = registry();
+    let solver = registry
+        .get(&day)
+        .ok_or_else(|| format!("Day {day} has not been solved"))?;
+    let input = source.read()?;
+
+    if let Some(format) = format {
+        let records = parts
+            .into_iter()
+            .map(|part| Record::new(*solver, part, &source.name(), &input))
+            .collect::<Vec<_>>();
+        print!("{}", report::write(&records, format));
+        return match records
+            .iter()
+            .find_map(|record| record.outcome.as_ref().err())
+        {
+            Some(e) => Err(e.clone()),
+            None => Ok(()),
+        };
+    }
+
+    for part in parts {
+        let run = solver.run(&input, part).map_err(|e| e.to_string())?;
+        let elapsed = run.parse + run.solve;
+        println!("Day {day} part {part} ({elapsed:.2?}):");
+        println!("{}", run.answer);
+    }
+    Ok(())
+}
+
+fn verify(VerifyArgs { day, manifest }: VerifyArgs) -> Result<(), String> {
+    let manifest = match manifest {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read `{}`: {e}", path.display()))?
+            .parse::<Manifest>()?,
+        None => Manifest::bundled(),
+    };
+    let outcomes = verify::verify(&manifest, day);
+    println!("{}", verify::Table(&outcomes));
+    match outcomes.iter().all(|outcome| outcome.passed()) {
+        true => Ok(()),
+        false => Err("Some answers did not match the manifest".to_string()),
+    }
+}
+
+fn bench(args: BenchArgs) -> Result<(), String> {
+    let (benchmarks, errors): (Vec<_>, Vec<_>) =
+        bench::bench_all(args.day, &args.input, args.iterations)
+            .into_iter()
+            .partition(Result::is_ok);
+    let benchmarks = benchmarks.into_iter().flatten().collect::<Vec<_>>();
+
+    println!("{}", bench::Table(&benchmarks));
+    if let Some(path) = args.output {
+        let tsv = benchmarks
+            .iter()
+            .fold(TSV_HEADER.to_string(), |tsv, benchmark| {
+                tsv + &benchmark.to_tsv()
+            });
+        std::fs::write(&path, tsv)
+            .map_err(|e| format!("Failed to write `{}`: {e}", path.display()))?;
+    }
+    match errors.into_iter().flat_map(Result::err).collect::<Vec<_>>() {
+        errors if errors.is_empty() => Ok(()),
+        errors => Err(errors.join("\n")),
+    }
+}
+
+fn report(args: ReportArgs) -> Result<(), String> {
+    let records = report::report_all(args.day, &args.input);
+    let output = report::write(&records, args.format);
+    match args.output {
+        Some(path) => std::fs::write(&path, output)
+            .map_err(|e| format!("Failed to write `{}`: {e}", path.display()))?,
+        None => print!("{output}"),
+    }
+    match records
+        .iter()
+        .filter(|record| record.outcome.is_err())
+        .count()
+    {
+        0 => Ok(()),
+        failed => Err(format!("{failed} of {} runs failed", records.len())),
+    }
+}
+
+fn render(args: RenderArgs) -> Result<(), String> {
+    let registry = registry();
+    let solver = registry
+        .get(&args.day)
+        .ok_or_else(|| format!("Day {} has not been solved", args.day))?;
+    let input = args.source.read()?;
+    let frame = solver
+        .render(&input)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Day {} has nothing to render", args.day))?;
+
+    let mut output = args.backend.encode(&frame, args.scale);
+    match args.output {
+        Some(path) => std::fs::write(&path, output)
+            .map_err(|e| format!("Failed to write `{}`: {e}", path.display())),
+        None => {
+            if matches!(args.backend, Backend::Text | Backend::Ansi) {
+                output.push(b'\n');
+            }
+            std::io::stdout()
+                .write_all(&output)
+                .map_err(|e| format!("Failed to write to stdout: {e}"))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match parse_args(&args) {
+        Ok(Command::Run(args)) => run(args),
+        Ok(Command::Verify(args)) => verify(args),
+        Ok(Command::Bench(args)) => bench(args),
+        Ok(Command::Report(args)) => report(args),
+        Ok(Command::Render(args)) => render(args),
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,32 +1,29 @@
-use std::cmp::Ordering;
-use std::iter::Sum;
+pub mod plan;
+pub mod stats;
 
-/// Wrapper representing an Elf, carrying `u64` calories.
-#[derive(Debug, Eq)]
-struct Elf(u64);
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
 
-impl Ord for Elf {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.0.cmp(&other.0)
-    }
-}
+use crate::{ParseError, Solution};
 
-impl PartialOrd for Elf {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// An Elf, identified by its position in the inventory, and the
+/// calories of each item it carries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Elf {
+    /// 0-based position of the Elf's block in the inventory
+    pub index: usize,
+    pub items: Vec<u64>,
 }
 
-impl PartialEq for Elf {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
-    }
-}
-
-impl Sum for Elf {
-    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        Self(iter.fold(0u64, |a, b| a + b.0))
-    }
+/// One of the Elves carrying the most calories, as returned by [`Elf::top_k`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ranked<'a> {
+    /// 1-based rank, where rank 1 carries the most calories
+    pub rank: usize,
+    pub index: usize,
+    pub total: u64,
+    pub items: &'a [u64],
 }
 
 impl Elf {
@@ -36,46 +33,230 @@ impl Elf {
     /// Each continuous block of integers represents the
     /// items carried by one Elf. Blocks are separated by
     /// a blank line.
-    fn accumulate(input: &str) -> Vec<Elf> {
-        input
-            .split("\n\n") // Split between blocks
-            .map(|values| {
-                values
-                    .split('\n') // Split between items
-                    .map(|calories| calories.parse::<u64>().unwrap())
-                    .sum()
-            })
-            .map(Elf)
+    fn accumulate(input: &str) -> Result<Vec<Elf>, ParseError> {
+        Elf::stream(input.as_bytes()).collect()
+    }
+
+    /// Streams elves from `reader` one block at a time, in constant memory.
+    ///
+    /// Accepts `\n` or `\r\n` line endings, whitespace around each count
+    /// and any number of blank lines between blocks. Stops after the first
+    /// line that is not a calorie count.
+    pub fn stream<R: BufRead>(reader: R) -> Elves<R> {
+        Elves {
+            reader,
+            line: String::new(),
+            index: 0,
+            elves: 0,
+            done: false,
+        }
+    }
+
+    /// Calories carried by this Elf, summed over every item.
+    pub fn total(&self) -> u64 {
+        self.items.iter().sum()
+    }
+
+    /// The `k` Elves carrying the most calories, most first.
+    ///
+    /// Keeps at most `k` Elves in a heap while scanning, so runs in
+    /// O(n log k). Elves with equal totals are ranked by index, earliest
+    /// first, so every Elf has a distinct rank.
+    pub fn top_k(elves: &[Elf], k: usize) -> Vec<Ranked<'_>> {
+        // Min-heap of the best Elves so far, keyed so that larger is better
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (position, elf) in elves.iter().enumerate() {
+            heap.push(Reverse((elf.total(), Reverse(elf.index), position)));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .enumerate()
+            .map(
+                |(rank, Reverse((total, Reverse(index), position)))| Ranked {
+                    rank: rank + 1,
+                    index,
+                    total,
+                    items: &elves[position].items,
+                },
+            )
             .collect()
     }
 
-    fn sum_largest_n(n: usize, mut elves: Vec<Elf>) -> Elf {
-        elves.sort(); // Sort ascending
-        elves.reverse(); // Sort descending
-        elves.into_iter().take(n).sum()
+    fn sum_largest_n(n: usize, elves: &[Elf]) -> u64 {
+        Elf::top_k(elves, n).iter().map(|ranked| ranked.total).sum()
+    }
+}
+
+/// Iterator over the elves in an inventory, created by [`Elf::stream`].
+pub struct Elves<R> {
+    reader: R,
+    /// Buffer reused for every line, so memory use doesn't grow with the input
+    line: String,
+    /// The 0-based index of the next line to be read
+    index: usize,
+    /// The number of Elves yielded so far
+    elves: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for Elves<R> {
+    type Item = Result<Elf, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut items = vec![];
+        let mut total = 0u64;
+        while !self.done {
+            self.line.clear();
+            let index = self.index;
+            let read = self.reader.read_line(&mut self.line);
+            self.index += 1;
+            let error = |reason: String, line: &str, token: &str| {
+                ParseError::new(Day1::DAY, reason).at(index, line, token)
+            };
+            let calories = match read {
+                // End of input closes the final block
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(_) => self.line.trim(),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(error(format!("Failed to read line: {e}"), "", "")));
+                }
+            };
+            if calories.is_empty() {
+                // A blank line closes the current block
+                match items.is_empty() {
+                    false => break,
+                    true => continue,
+                }
+            }
+            let line = self.line.trim_end_matches(['\r', '\n']);
+            let item = calories
+                .parse::<u64>()
+                .map_err(|e| format!("Invalid calorie count: {e}"))
+                .and_then(|item| {
+                    total
+                        .checked_add(item)
+                        .map(|sum| (item, sum))
+                        .ok_or_else(|| "Calorie total overflows".to_string())
+                });
+            match item {
+                Ok((item, sum)) => {
+                    items.push(item);
+                    total = sum;
+                }
+                Err(reason) => {
+                    self.done = true;
+                    return Some(Err(error(reason, line, calories)));
+                }
+            }
+        }
+        if items.is_empty() {
+            return None;
+        }
+        self.elves += 1;
+        Some(Ok(Elf {
+            index: self.elves - 1,
+            items,
+        }))
+    }
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Elf>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Elf::accumulate(input)
+    }
+
+    fn part_one(elves: &Self::Input) -> Self::PartOne {
+        elves.iter().map(Elf::total).max().unwrap_or(0)
+    }
+
+    fn part_two(elves: &Self::Input) -> Self::PartTwo {
+        Elf::sum_largest_n(3, elves)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn challenge_1() {
         let input = include_str!("input").trim();
-        let mut elves = Elf::accumulate(input);
-        elves.sort();
-        let max_value = elves.pop();
+        let elves = Elf::accumulate(input).unwrap();
+        let max_value = elves.iter().map(Elf::total).max();
         assert!(max_value.is_some());
         let max_value = max_value.unwrap();
-        assert_eq!(max_value.0, 71023);
+        assert_answer(1, Part::One, "input", max_value);
     }
 
     #[test]
     fn challenge_2() {
         let input = include_str!("input").trim();
-        let elves = Elf::accumulate(input);
-        let largest_three_sum = Elf::sum_largest_n(3, elves);
-        assert_eq!(largest_three_sum.0, 206289);
+        let elves = Elf::accumulate(input).unwrap();
+        let largest_three_sum = Elf::sum_largest_n(3, &elves);
+        assert_answer(1, Part::Two, "input", largest_three_sum);
+    }
+
+    #[test]
+    fn tolerates_crlf_and_extra_blank_lines() {
+        let input = "\r\n1000\r\n2000  \r\n\r\n\r\n \t\r\n3000\r\n\r\n\r\n";
+        let elves = Elf::stream(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let totals = elves.iter().map(Elf::total).collect::<Vec<_>>();
+        assert_eq!(totals, [3000, 3000]);
+        assert_eq!(elves[1].items, [3000]);
+    }
+
+    #[test]
+    fn streams_elves_until_an_invalid_line() {
+        let mut elves = Elf::stream("1\n2\n\n3\r\n  x4\r\n\n5".as_bytes());
+        assert_eq!(elves.next().unwrap().unwrap().total(), 3);
+        let error = elves.next().unwrap().unwrap_err();
+        assert_eq!((error.line, error.column), (5, 3));
+        assert_eq!(error.text, "x4");
+        assert_eq!(elves.next(), None);
+    }
+
+    #[test]
+    fn ranks_top_elves_with_ties_by_index() {
+        let elves = Elf::accumulate("5\n\n4\n4\n\n1\n\n2\n6\n\n9").unwrap();
+        let ranked = Elf::top_k(&elves, 3)
+            .iter()
+            .map(|r| (r.rank, r.index, r.total, r.items.to_vec()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            [
+                (1, 4, 9, vec![9]),
+                (2, 1, 8, vec![4, 4]),
+                (3, 3, 8, vec![2, 6])
+            ]
+        );
+        assert_eq!(Elf::top_k(&elves, 10).len(), 5);
+        assert!(Elf::top_k(&elves, 0).is_empty());
+    }
+
+    #[test]
+    fn reports_invalid_calories() {
+        let error = Elf::accumulate("1000\n2000\n\n3x00").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.text, "3x00");
     }
 }
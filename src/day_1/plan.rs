@@ -0,0 +1,310 @@
+use std::cmp::Reverse;
+use std::fmt::{self, Display};
+
+use super::Elf;
+
+/// Most items the exact solver is used for when choosing automatically.
+pub const EXACT_LIMIT: usize = 24;
+
+/// How to search for an assignment of items to Elves.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Branch and bound, which finds the smallest heaviest load but takes
+    /// exponential time in the worst case.
+    Exact,
+    /// Longest processing time first: place each item, largest first, with
+    /// the Elf carrying the least so far.
+    Greedy,
+    /// Exact for at most [`EXACT_LIMIT`] items, greedy otherwise.
+    Auto,
+}
+
+/// One item, identified by the Elf that carried it originally.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    /// Index of the Elf the item started with
+    pub elf: usize,
+    /// Position of the item in that Elf's inventory
+    pub position: usize,
+    pub calories: u64,
+}
+
+/// An item handed from one Elf to another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub item: Item,
+    pub to: usize,
+}
+
+/// A reassignment of every item among a number of Elves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    /// Index of each Elf in the plan. Elves are matched with an original
+    /// Elf where possible, and any extra are numbered after the originals.
+    pub elves: Vec<usize>,
+    /// The items each Elf carries after the moves
+    pub items: Vec<Vec<Item>>,
+    /// Total calories each Elf carries after the moves
+    pub loads: Vec<u64>,
+    pub max_load: u64,
+    /// Difference between the heaviest and lightest loads
+    pub imbalance: u64,
+    /// No assignment can have a heaviest load below this
+    pub lower_bound: u64,
+    /// Whether `max_load` is known to be the smallest possible
+    pub optimal: bool,
+    pub moves: Vec<Move>,
+}
+
+impl Plan {
+    /// Reassigns every item carried by `elves` among `k` Elves, minimising
+    /// the heaviest load. Returns `None` if `k` is zero.
+    pub fn new(elves: &[Elf], k: usize, strategy: Strategy) -> Option<Self> {
+        if k == 0 {
+            return None;
+        }
+        let mut items = elves
+            .iter()
+            .flat_map(|elf| {
+                elf.items
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &calories)| Item {
+                        elf: elf.index,
+                        position,
+                        calories,
+                    })
+            })
+            .collect::<Vec<_>>();
+        // Largest first, keeping the original order between equal items
+        items.sort_by_key(|item| Reverse(item.calories));
+
+        let total = items.iter().map(|item| item.calories).sum::<u64>();
+        let largest = items.first().map_or(0, |item| item.calories);
+        let lower_bound = total.div_ceil(k as u64).max(largest);
+
+        let greedy = lpt(&items, k);
+        let exact = match strategy {
+            Strategy::Exact => true,
+            Strategy::Greedy => false,
+            Strategy::Auto => items.len() <= EXACT_LIMIT,
+        };
+        let (bins, optimal) = match exact {
+            true => (branch_and_bound(&items, k, greedy, lower_bound), true),
+            false => {
+                let optimal = max_load(&greedy, &items, k) == lower_bound;
+                (greedy, optimal)
+            }
+        };
+
+        let mut groups = vec![vec![]; k];
+        for (item, &bin) in items.iter().zip(&bins) {
+            groups[bin].push(*item);
+        }
+        for group in &mut groups {
+            group.sort_by_key(|item| (item.elf, item.position));
+        }
+        let labels = match_elves(&groups, elves);
+
+        let loads = groups
+            .iter()
+            .map(|group| group.iter().map(|item| item.calories).sum())
+            .collect::<Vec<u64>>();
+        let moves = groups
+            .iter()
+            .zip(&labels)
+            .flat_map(|(group, &to)| {
+                group
+                    .iter()
+                    .filter(move |item| item.elf != to)
+                    .map(move |&item| Move { item, to })
+            })
+            .collect();
+        Some(Self {
+            max_load: *loads.iter().max().unwrap(),
+            imbalance: loads.iter().max().unwrap() - loads.iter().min().unwrap(),
+            elves: labels,
+            items: groups,
+            loads,
+            lower_bound,
+            optimal,
+            moves,
+        })
+    }
+}
+
+/// The bin for each of `items` chosen by placing it with the lightest bin.
+fn lpt(items: &[Item], k: usize) -> Vec<usize> {
+    let mut loads = vec![0u64; k];
+    items
+        .iter()
+        .map(|item| {
+            let bin = (0..k).min_by_key(|&bin| loads[bin]).unwrap();
+            loads[bin] += item.calories;
+            bin
+        })
+        .collect()
+}
+
+fn max_load(bins: &[usize], items: &[Item], k: usize) -> u64 {
+    let mut loads = vec![0u64; k];
+    for (item, &bin) in items.iter().zip(bins) {
+        loads[bin] += item.calories;
+    }
+    loads.into_iter().max().unwrap_or(0)
+}
+
+/// Searches every assignment of `items` (sorted largest first) to `k` bins
+/// for one with a lighter heaviest bin than `initial`.
+fn branch_and_bound(items: &[Item], k: usize, initial: Vec<usize>, lower_bound: u64) -> Vec<usize> {
+    struct Search<'a> {
+        items: &'a [Item],
+        loads: Vec<u64>,
+        bins: Vec<usize>,
+        best: u64,
+        best_bins: Vec<usize>,
+        lower_bound: u64,
+    }
+
+    impl Search<'_> {
+        fn assign(&mut self, next: usize, heaviest: u64) {
+            if self.best == self.lower_bound {
+                return;
+            }
+            let Some(item) = self.items.get(next) else {
+                self.best = heaviest;
+                self.best_bins = self.bins.clone();
+                return;
+            };
+            let mut tried = vec![];
+            for bin in 0..self.loads.len() {
+                let load = self.loads[bin] + item.calories;
+                // Bins with equal loads lead to equivalent assignments
+                if load >= self.best || tried.contains(&self.loads[bin]) {
+                    continue;
+                }
+                tried.push(self.loads[bin]);
+                self.loads[bin] = load;
+                self.bins[next] = bin;
+                self.assign(next + 1, heaviest.max(load));
+                self.loads[bin] -= item.calories;
+            }
+        }
+    }
+
+    let mut search = Search {
+        items,
+        loads: vec![0; k],
+        bins: vec![0; items.len()],
+        best: max_load(&initial, items, k),
+        best_bins: initial,
+        lower_bound,
+    };
+    search.assign(0, 0);
+    search.best_bins
+}
+
+/// Matches each group with a distinct original Elf, greedily pairing those
+/// that already share the most items, so few items need to move.
+///
+/// Groups left over are given new indices after the original Elves.
+fn match_elves(groups: &[Vec<Item>], elves: &[Elf]) -> Vec<usize> {
+    let mut shared = vec![];
+    for (group, items) in groups.iter().enumerate() {
+        for elf in elves {
+            let count = items.iter().filter(|item| item.elf == elf.index).count();
+            if count > 0 {
+                shared.push((Reverse(count), group, elf.index));
+            }
+        }
+    }
+    shared.sort();
+
+    let mut labels = vec![None; groups.len()];
+    let mut taken = vec![];
+    for (_, group, elf) in shared {
+        if labels[group].is_none() && !taken.contains(&elf) {
+            labels[group] = Some(elf);
+            taken.push(elf);
+        }
+    }
+    let mut unused = elves
+        .iter()
+        .map(|elf| elf.index)
+        .filter(|index| !taken.contains(index))
+        .chain(elves.iter().map(|elf| elf.index + 1).max().unwrap_or(0)..);
+    labels
+        .into_iter()
+        .map(|label| label.unwrap_or_else(|| unused.next().unwrap()))
+        .collect()
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Max load:    {}{}",
+            self.max_load,
+            if self.optimal { " (optimal)" } else { "" }
+        )?;
+        writeln!(f, "Lower bound: {}", self.lower_bound)?;
+        writeln!(f, "Imbalance:   {}", self.imbalance)?;
+        write!(f, "Moves:       {}", self.moves.len())?;
+        for ((elf, load), items) in self.elves.iter().zip(&self.loads).zip(&self.items) {
+            write!(f, "\n  Elf {elf:<6} {load:>8}  {} items", items.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elves(input: &str) -> Vec<Elf> {
+        Elf::accumulate(input).unwrap()
+    }
+
+    #[test]
+    fn exact_beats_greedy() {
+        // LPT places 3, 3, 2, 2, 2 as {3, 2, 2}, {3, 2}, a heaviest load of 7
+        let elves = elves("3\n3\n2\n2\n2");
+        let greedy = Plan::new(&elves, 2, Strategy::Greedy).unwrap();
+        assert_eq!((greedy.max_load, greedy.optimal), (7, false));
+        let exact = Plan::new(&elves, 2, Strategy::Exact).unwrap();
+        assert_eq!((exact.max_load, exact.imbalance), (6, 0));
+        assert!(exact.optimal);
+    }
+
+    #[test]
+    fn reports_moves_from_original_elves() {
+        let elves = elves(include_str!("test"));
+        let plan = Plan::new(&elves, 5, Strategy::Auto).unwrap();
+        assert_eq!(plan.max_load, 11000);
+        assert_eq!(plan.lower_bound, 11000);
+        assert_eq!(plan.loads.iter().sum::<u64>(), 45000 + 10000);
+        // Every item that moves ends up with a different Elf
+        assert!(plan.moves.iter().all(|m| m.item.elf != m.to));
+        let carried = plan.items.iter().map(Vec::len).sum::<usize>();
+        assert_eq!(carried, 10);
+    }
+
+    #[test]
+    fn keeps_items_in_place_when_already_balanced() {
+        let elves = elves("4\n\n2\n2\n\n1\n3");
+        let plan = Plan::new(&elves, 3, Strategy::Auto).unwrap();
+        assert_eq!(plan.max_load, 4);
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn numbers_extra_elves_after_the_originals() {
+        let elves = elves("5\n5");
+        let plan = Plan::new(&elves, 2, Strategy::Auto).unwrap();
+        let mut labels = plan.elves.clone();
+        labels.sort();
+        assert_eq!(labels, [0, 1]);
+        assert_eq!(plan.moves.len(), 1);
+        assert!(Plan::new(&elves, 0, Strategy::Auto).is_none());
+    }
+}
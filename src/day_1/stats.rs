@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use super::Elf;
+
+/// Widest bar drawn in the histogram of totals.
+const BAR_WIDTH: usize = 40;
+
+/// A range of calorie totals and how many Elves carry a total within it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bin {
+    /// Inclusive lower bound
+    pub start: u64,
+    /// Exclusive upper bound
+    pub end: u64,
+    pub count: usize,
+}
+
+/// Which side of the interquartile fences an outlier lies on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Low,
+    High,
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Low => f.pad("low"),
+            Side::High => f.pad("high"),
+        }
+    }
+}
+
+/// An Elf whose total lies more than 1.5 interquartile ranges outside the
+/// middle half of all totals.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outlier {
+    pub index: usize,
+    pub total: u64,
+    pub side: Side,
+}
+
+/// Descriptive statistics over the calorie totals of an inventory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    pub count: usize,
+    pub total: u64,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+    /// Population standard deviation
+    pub std_dev: f64,
+    /// The 25th and 75th percentiles
+    pub quartiles: (f64, f64),
+    /// Number of Elves carrying each number of items
+    pub items_per_elf: BTreeMap<usize, usize>,
+    pub histogram: Vec<Bin>,
+    pub outliers: Vec<Outlier>,
+    /// Every total in ascending order, for percentile queries
+    sorted: Vec<u64>,
+}
+
+impl Statistics {
+    /// Summarises `elves`, grouping totals into `bins` equal-width bins.
+    ///
+    /// Returns `None` if there are no Elves.
+    pub fn new(elves: &[Elf], bins: usize) -> Option<Self> {
+        let mut sorted = elves.iter().map(Elf::total).collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let count = sorted.len();
+        let total = sorted.iter().sum::<u64>();
+        let mean = total as f64 / count as f64;
+        let variance = sorted
+            .iter()
+            .map(|&value| (value as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        let mut items_per_elf = BTreeMap::new();
+        for elf in elves {
+            *items_per_elf.entry(elf.items.len()).or_insert(0) += 1;
+        }
+
+        let mut statistics = Self {
+            count,
+            total,
+            min,
+            max,
+            mean,
+            median: 0.0,
+            std_dev: variance.sqrt(),
+            quartiles: (0.0, 0.0),
+            items_per_elf,
+            histogram: histogram(&sorted, bins.max(1)),
+            outliers: vec![],
+            sorted,
+        };
+        statistics.median = statistics.percentile(50.0);
+        statistics.quartiles = (statistics.percentile(25.0), statistics.percentile(75.0));
+
+        let (q1, q3) = statistics.quartiles;
+        let (low, high) = (q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1));
+        statistics.outliers = elves
+            .iter()
+            .filter_map(|elf| {
+                let total = elf.total();
+                let side = match total as f64 {
+                    value if value < low => Side::Low,
+                    value if value > high => Side::High,
+                    _ => return None,
+                };
+                Some(Outlier {
+                    index: elf.index,
+                    total,
+                    side,
+                })
+            })
+            .collect();
+        Some(statistics)
+    }
+
+    /// The `p`th percentile of the totals, for `p` between 0 and 100,
+    /// interpolating linearly between the closest ranks.
+    pub fn percentile(&self, p: f64) -> f64 {
+        let rank = p.clamp(0.0, 100.0) / 100.0 * (self.count - 1) as f64;
+        let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+        let (low, high) = (self.sorted[below] as f64, self.sorted[above] as f64);
+        low + (high - low) * rank.fract()
+    }
+
+    /// The statistics as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let items_per_elf = self
+            .items_per_elf
+            .iter()
+            .map(|(items, elves)| format!("\"{items}\":{elves}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let histogram = self
+            .histogram
+            .iter()
+            .map(|bin| {
+                format!(
+                    "{{\"start\":{},\"end\":{},\"count\":{}}}",
+                    bin.start, bin.end, bin.count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let outliers = self
+            .outliers
+            .iter()
+            .map(|outlier| {
+                format!(
+                    "{{\"index\":{},\"total\":{},\"side\":\"{}\"}}",
+                    outlier.index, outlier.total, outlier.side
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"count\":{},\"total\":{},\"min\":{},\"max\":{},\"mean\":{},\"median\":{},\"std_dev\":{},\"q1\":{},\"q3\":{},\"items_per_elf\":{{{items_per_elf}}},\"histogram\":[{histogram}],\"outliers\":[{outliers}]}}",
+            self.count,
+            self.total,
+            self.min,
+            self.max,
+            self.mean,
+            self.median,
+            self.std_dev,
+            self.quartiles.0,
+            self.quartiles.1,
+        )
+    }
+}
+
+/// Counts the sorted `totals` falling into `bins` equal-width bins.
+fn histogram(totals: &[u64], bins: usize) -> Vec<Bin> {
+    let (min, max) = (totals[0], totals[totals.len() - 1]);
+    let width = ((max - min) / bins as u64 + 1).max(1);
+    let mut histogram = (0..bins as u64)
+        .map(|bin| Bin {
+            start: min + bin * width,
+            end: min + (bin + 1) * width,
+            count: 0,
+        })
+        .collect::<Vec<_>>();
+    for &total in totals {
+        let bin = (((total - min) / width) as usize).min(bins - 1);
+        histogram[bin].count += 1;
+    }
+    histogram
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Elves:      {}", self.count)?;
+        writeln!(f, "Total:      {}", self.total)?;
+        writeln!(f, "Min:        {}", self.min)?;
+        writeln!(f, "Max:        {}", self.max)?;
+        writeln!(f, "Mean:       {:.1}", self.mean)?;
+        writeln!(f, "Median:     {:.1}", self.median)?;
+        writeln!(f, "Std dev:    {:.1}", self.std_dev)?;
+        writeln!(
+            f,
+            "Quartiles:  {:.1} / {:.1}",
+            self.quartiles.0, self.quartiles.1
+        )?;
+
+        writeln!(f, "\nItems per Elf:")?;
+        for (items, elves) in &self.items_per_elf {
+            writeln!(f, "{items:>6}  {elves}")?;
+        }
+
+        writeln!(f, "\nTotals:")?;
+        let largest = self
+            .histogram
+            .iter()
+            .map(|bin| bin.count)
+            .max()
+            .unwrap_or(0);
+        let label_width = self.max.to_string().len();
+        for bin in &self.histogram {
+            let bar = "\u{2588}".repeat((bin.count * BAR_WIDTH).div_ceil(largest.max(1)));
+            writeln!(
+                f,
+                "{:>label_width$} - {:>label_width$}  {bar} {}",
+                bin.start, bin.end, bin.count
+            )?;
+        }
+
+        write!(f, "\nOutliers:")?;
+        if self.outliers.is_empty() {
+            write!(f, " none")?;
+        }
+        for outlier in &self.outliers {
+            write!(
+                f,
+                "\n  Elf {:<6} {:>label_width$}  {}",
+                outlier.index, outlier.total, outlier.side
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Elf> {
+        Elf::accumulate(include_str!("test")).unwrap()
+    }
+
+    #[test]
+    fn summarises_totals() {
+        let statistics = Statistics::new(&example(), 4).unwrap();
+        assert_eq!((statistics.count, statistics.total), (5, 55000));
+        assert_eq!((statistics.min, statistics.max), (4000, 24000));
+        assert_eq!(statistics.mean, 11000.0);
+        assert_eq!(statistics.median, 10000.0);
+        assert_eq!(statistics.quartiles, (6000.0, 11000.0));
+        assert_eq!(statistics.percentile(90.0), 18800.0);
+        assert!((statistics.std_dev - 6985.7).abs() < 0.1);
+        assert_eq!(
+            statistics.items_per_elf,
+            BTreeMap::from([(1, 2), (2, 1), (3, 2)])
+        );
+    }
+
+    #[test]
+    fn flags_outliers_and_bins_totals() {
+        let statistics = Statistics::new(&example(), 4).unwrap();
+        assert_eq!(
+            statistics.outliers,
+            [Outlier {
+                index: 3,
+                total: 24000,
+                side: Side::High
+            }]
+        );
+        let counts = statistics
+            .histogram
+            .iter()
+            .map(|bin| bin.count)
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [2, 2, 0, 1]);
+        assert!(statistics.to_string().contains("Elf 3"));
+        assert!(statistics
+            .to_json()
+            .contains("\"outliers\":[{\"index\":3,\"total\":24000,\"side\":\"high\"}]"));
+    }
+
+    #[test]
+    fn has_no_statistics_without_elves() {
+        assert_eq!(Statistics::new(&[], 4), None);
+    }
+}
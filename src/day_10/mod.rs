@@ -1,133 +1,203 @@
-#[cfg(test)]
-mod tests {
-    use std::{i16, str::FromStr};
+use std::str::FromStr;
 
-    enum Instruction {
-        NoOp,
-        AddX(i16),
-    }
+use crate::grid::Grid;
+use crate::render::{self, Cell, Frame, Render, Rgb};
+use crate::{ParseError, Solution};
 
-    impl FromStr for Instruction {
-        type Err = String;
+#[derive(Debug)]
+pub enum Instruction {
+    NoOp,
+    AddX(i16),
+}
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            Ok(match s.split_once(" ") {
-                Some((_addx, value)) => {
-                    Self::AddX(value.parse::<i16>().expect("Failed to parse number"))
-                }
-                None => Self::NoOp,
-            })
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: &str, token: &str| ParseError::new(Day10::DAY, reason).at(0, s, token);
+        match s.split_once(' ') {
+            Some(("addx", value)) => Ok(Self::AddX(
+                value
+                    .parse::<i16>()
+                    .map_err(|_| error("Failed to parse number", value))?,
+            )),
+            None if s == "noop" => Ok(Self::NoOp),
+            _ => Err(error("Expected `noop` or `addx <value>`", s)),
         }
     }
+}
 
-    fn part1(input: &str) -> i16 {
-        let mut last_x = 1;
-        let total_cycles = input.lines().fold(0, |sum, line| {
-            sum + match line.parse::<Instruction>().ok().unwrap() {
-                Instruction::NoOp => 1,
-                Instruction::AddX(_) => 2,
-            }
-        });
-        let evaluation_cycles = (0..((total_cycles + 20) / 40))
-            .map(|val| 20 + 40 * val)
-            .collect::<Vec<_>>();
-        let mut iter = input
-            .lines()
-            .scan((0, 1), |(cycle, x), line| {
-                match line.parse::<Instruction>().ok()? {
-                    Instruction::NoOp => *cycle += 1,
-                    Instruction::AddX(dx) => {
-                        *cycle += 2;
-                        *x += dx;
-                    }
-                }
-                Some((*cycle, *x))
-            })
-            .peekable();
-
-        evaluation_cycles
-            .iter()
-            .map(|tap| {
-                while let Some((_, x)) = iter.peek().filter(|(cycle, _)| cycle < tap) {
-                    last_x = *x;
-                    iter.next();
+fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.parse::<Instruction>()
+                .map_err(|e| e.within(index, line, line))
+        })
+        .collect()
+}
+
+fn part1(instructions: &[Instruction]) -> i16 {
+    let mut last_x = 1;
+    let total_cycles = instructions.iter().fold(0, |sum, instruction| {
+        sum + match instruction {
+            Instruction::NoOp => 1,
+            Instruction::AddX(_) => 2,
+        }
+    });
+    let evaluation_cycles = (0..((total_cycles + 20) / 40))
+        .map(|val| 20 + 40 * val)
+        .collect::<Vec<_>>();
+    let mut iter = instructions
+        .iter()
+        .scan((0, 1), |(cycle, x), instruction| {
+            match instruction {
+                Instruction::NoOp => *cycle += 1,
+                Instruction::AddX(dx) => {
+                    *cycle += 2;
+                    *x += dx;
                 }
-                tap * last_x
-            })
-            .sum()
+            }
+            Some((*cycle, *x))
+        })
+        .peekable();
+
+    evaluation_cycles
+        .iter()
+        .map(|tap| {
+            while let Some((_, x)) = iter.peek().filter(|(cycle, _)| cycle < tap) {
+                last_x = *x;
+                iter.next();
+            }
+            tap * last_x
+        })
+        .sum()
+}
+
+/// Width of the CRT screen in pixels.
+const SCREEN_WIDTH: usize = 40;
+
+/// The CRT screen, with each pixel lit or dark.
+pub struct Crt(Grid<bool>);
+
+impl Render for Crt {
+    fn render(&self) -> Frame {
+        self.0.map(|&lit| match lit {
+            true => Cell::new('\u{2593}', Rgb::new(120, 255, 120)),
+            false => Cell::new('\u{2591}', Rgb::new(20, 40, 20)),
+        })
     }
+}
 
-    fn part2(input: &str) -> String {
-        let mut last_x = 1;
-        let mut iter = input
-            .lines()
-            .scan((0, 1), |(cycle, x), line| {
-                match line.parse::<Instruction>().ok()? {
-                    Instruction::NoOp => *cycle += 1,
-                    Instruction::AddX(dx) => {
-                        *cycle += 2;
-                        *x += dx;
-                    }
+fn draw(instructions: &[Instruction]) -> Crt {
+    let mut last_x = 1;
+    let mut iter = instructions
+        .iter()
+        .scan((0, 1), |(cycle, x), instruction| {
+            match instruction {
+                Instruction::NoOp => *cycle += 1,
+                Instruction::AddX(dx) => {
+                    *cycle += 2;
+                    *x += dx;
                 }
-                Some((*cycle, *x))
-            })
-            .peekable();
-
-        let total_cycles = input.lines().fold(0, |sum, line| {
-            sum + match line.parse::<Instruction>().ok().unwrap() {
-                Instruction::NoOp => 1,
-                Instruction::AddX(_) => 2,
             }
-        });
-        (0..total_cycles)
-            .step_by(40)
-            .map(|row| {
-                (0..40)
-                    .map(|col| {
-                        while let Some((_, x)) =
-                            iter.peek().filter(|(cycle, _)| cycle <= &(row + col))
-                        {
-                            last_x = *x;
-                            iter.next();
-                        }
-                        if (-1..=1).contains(&(last_x - col)) {
-                            '\u{2593}'
-                        } else {
-                            '\u{2591}'
-                        }
-                    })
-                    .collect::<String>()
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
+            Some((*cycle, *x))
+        })
+        .peekable();
+
+    let total_cycles = instructions.iter().fold(0, |sum, instruction| {
+        sum + match instruction {
+            Instruction::NoOp => 1,
+            Instruction::AddX(_) => 2,
+        }
+    });
+    let pixels = (0..total_cycles)
+        .step_by(SCREEN_WIDTH)
+        .flat_map(|row| (0..SCREEN_WIDTH as i16).map(move |col| (row, col)))
+        .map(|(row, col)| {
+            while let Some((_, x)) = iter.peek().filter(|(cycle, _)| cycle <= &(row + col)) {
+                last_x = *x;
+                iter.next();
+            }
+            (-1..=1).contains(&(last_x - col))
+        })
+        .collect::<Vec<_>>();
+    let height = pixels.len() / SCREEN_WIDTH;
+    Crt(Grid::from_fn(SCREEN_WIDTH, height, |position| {
+        pixels[position.y * SCREEN_WIDTH + position.x]
+    }))
+}
+
+fn part2(instructions: &[Instruction]) -> String {
+    render::text(&draw(instructions).render())
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Instruction>;
+    type PartOne = i16;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input.trim_end())
     }
 
+    fn part_one(instructions: &Self::Input) -> Self::PartOne {
+        part1(instructions)
+    }
+
+    fn part_two(instructions: &Self::Input) -> Self::PartTwo {
+        part2(instructions)
+    }
+
+    fn render(instructions: &Self::Input) -> Option<Frame> {
+        Some(draw(instructions).render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
+
     #[test]
     fn day_10_test_1() {
         let input = include_str!("test").trim();
 
-        let result = part1(input);
-        assert_eq!(result, 13140);
+        let result = part1(&parse(input).unwrap());
+        assert_answer(10, Part::One, "test", result);
     }
 
     #[test]
     fn day_10_challenge_1() {
         let input = include_str!("input").trim();
-        let result = part1(input);
-        assert_eq!(result, 15120);
+        let result = part1(&parse(input).unwrap());
+        assert_answer(10, Part::One, "input", result);
     }
 
     #[test]
     fn day_10_test_2() {
         let input = include_str!("test").trim();
-        let result = part2(input);
-        println!("{result}");
+        let result = part2(&parse(input).unwrap());
+        assert_answer(10, Part::Two, "test", result);
     }
 
     #[test]
     fn day_10_challenge_2() {
         let input = include_str!("input").trim();
-        let result = part2(input);
-        println!("{result}");
+        let result = part2(&parse(input).unwrap());
+        assert_answer(10, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_invalid_value() {
+        let error = parse("noop\naddx 3\naddx -x").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 6));
+        assert_eq!(error.text, "-x");
     }
 }
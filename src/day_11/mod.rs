@@ -1,213 +1,309 @@
-#[cfg(test)]
-mod tests {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        str::FromStr,
-    };
+use std::{
+    collections::{BTreeMap, VecDeque},
+    str::FromStr,
+};
 
-    #[derive(Clone, Copy, Debug)]
-    enum Operation {
-        Add(u64),
-        Mul(u64),
-        Square,
-    }
+use crate::{ParseError, Solution};
 
-    impl Default for Operation {
-        fn default() -> Self {
-            Self::Add(0)
-        }
-    }
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    Add(u64),
+    Mul(u64),
+    Square,
+}
 
-    #[derive(Debug, Default)]
-    struct Monkey {
-        items: VecDeque<u64>,
-        divisor: u64,
-        if_true: usize,
-        if_false: usize,
-        operation: Operation,
-        num_inspections: usize,
-    }
+#[derive(Clone, Debug)]
+pub struct Monkey {
+    items: VecDeque<u64>,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
+    operation: Operation,
+    num_inspections: usize,
+}
 
-    fn test_worry(worry_level: u64, divisor: u64) -> bool {
-        (worry_level % divisor) == 0
-    }
+fn test_worry(worry_level: u64, divisor: u64) -> bool {
+    worry_level.is_multiple_of(divisor)
+}
 
-    fn operate(worry_level: u64, operation: Operation) -> u64 {
-        match operation {
-            Operation::Square => worry_level * worry_level,
-            Operation::Add(x) => worry_level + x,
-            Operation::Mul(x) => worry_level * x,
-        }
+fn operate(worry_level: u64, operation: Operation) -> u64 {
+    match operation {
+        Operation::Square => worry_level * worry_level,
+        Operation::Add(x) => worry_level + x,
+        Operation::Mul(x) => worry_level * x,
     }
+}
 
-    fn operate_re(worry_level: u64, operation: Operation, p: u64) -> u64 {
-        match operation {
-            Operation::Square => ((worry_level % p) * (worry_level % p)) % p,
-            Operation::Add(x) => ((worry_level % p) + (x % p)) % p,
-            Operation::Mul(x) => ((worry_level % p) * (x % p)) % p,
-        }
+fn operate_re(worry_level: u64, operation: Operation, p: u64) -> u64 {
+    match operation {
+        Operation::Square => ((worry_level % p) * (worry_level % p)) % p,
+        Operation::Add(x) => ((worry_level % p) + (x % p)) % p,
+        Operation::Mul(x) => ((worry_level % p) * (x % p)) % p,
     }
+}
 
-    impl FromStr for Monkey {
-        type Err = String;
-
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let mut monkey = Monkey::default();
-            s.lines()
-                .skip(1)
-                .for_each(|line| match line.trim().split_once(":") {
-                    Some((label, items)) if label == "Starting items" => {
-                        monkey.items = items
-                            .split(",")
-                            .map(|item| item.trim().parse::<u64>().unwrap())
-                            .collect();
-                    }
-                    Some((label, statement)) if label == "Test" => {
-                        monkey.divisor = statement
-                            .rsplit_once(" ")
-                            .unwrap()
-                            .1
-                            .parse::<u64>()
-                            .unwrap();
-                    }
-                    Some((label, items)) if label == "If true" => {
-                        monkey.if_true = items.rsplit_once(" ").unwrap().1.parse::<usize>().unwrap()
-                    }
-                    Some((label, items)) if label == "If false" => {
-                        monkey.if_false =
-                            items.rsplit_once(" ").unwrap().1.parse::<usize>().unwrap()
+impl FromStr for Monkey {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items = None;
+        let mut operation = None;
+        let mut divisor = None;
+        let mut if_true = None;
+        let mut if_false = None;
+        for (index, line) in s.lines().enumerate().skip(1) {
+            let error = |reason: &str, token: &str| {
+                ParseError::new(Day11::DAY, reason).at(index, line, token)
+            };
+            // The number a statement refers to is always its final word
+            let last_number = |statement: &str| {
+                let word = statement.rsplit(' ').next().unwrap_or(statement);
+                word.parse::<u64>()
+                    .map_err(|_| error("Expected a number", word))
+            };
+            match line.trim().split_once(':') {
+                Some(("Starting items", list)) => {
+                    items = Some(
+                        list.split(',')
+                            .map(|item| {
+                                let item = item.trim();
+                                item.parse::<u64>()
+                                    .map_err(|_| error("Failed to parse worry level", item))
+                            })
+                            .collect::<Result<_, _>>()?,
+                    );
+                }
+                Some(("Test", statement)) => {
+                    divisor = match last_number(statement)? {
+                        0 => return Err(error("Divisor must be non-zero", statement)),
+                        divisor => Some(divisor),
                     }
-                    Some((label, items)) if label == "Operation" => {
-                        let substrings = items.rsplitn(3, " ").collect::<Vec<_>>();
-                        monkey.operation = match substrings[0] {
-                            x if x == "old" => Operation::Square,
-                            number => {
-                                let number = number.parse::<u64>().unwrap();
-                                match substrings[1].trim() {
-                                    "+" => Operation::Add(number),
-                                    "*" => Operation::Mul(number),
-                                    _ => unreachable!("Malformed Input"),
-                                }
+                }
+                Some(("If true", statement)) => if_true = Some(last_number(statement)? as usize),
+                Some(("If false", statement)) => if_false = Some(last_number(statement)? as usize),
+                Some(("Operation", statement)) => {
+                    let substrings = statement.rsplitn(3, ' ').collect::<Vec<_>>();
+                    operation = Some(match substrings[..] {
+                        ["old", _, _] => Operation::Square,
+                        [number, operator, _] => {
+                            let number = number
+                                .parse::<u64>()
+                                .map_err(|_| error("Expected a number or `old`", number))?;
+                            match operator {
+                                "+" => Operation::Add(number),
+                                "*" => Operation::Mul(number),
+                                _ => return Err(error("Expected `+` or `*`", operator)),
                             }
-                        };
-                    }
-                    _ => {}
-                });
-            Ok(monkey)
+                        }
+                        _ => return Err(error("Malformed operation", statement)),
+                    });
+                }
+                _ => return Err(error("Unrecognised monkey attribute", line)),
+            }
         }
+        let header = s.lines().next().unwrap_or(s);
+        let missing = |attribute: &str| {
+            ParseError::new(Day11::DAY, format!("Missing `{attribute}` line")).at(0, header, header)
+        };
+        Ok(Monkey {
+            items: items.ok_or_else(|| missing("Starting items"))?,
+            divisor: divisor.ok_or_else(|| missing("Test"))?,
+            if_true: if_true.ok_or_else(|| missing("If true"))?,
+            if_false: if_false.ok_or_else(|| missing("If false"))?,
+            operation: operation.ok_or_else(|| missing("Operation"))?,
+            num_inspections: 0,
+        })
     }
+}
+
+fn parse(input: &str) -> Result<BTreeMap<usize, Monkey>, ParseError> {
+    let count = input.split("\n\n").count();
+    // Line on which the current monkey's block starts
+    let mut offset = 0;
+    input
+        .split("\n\n")
+        .enumerate()
+        .map(|(idx, block)| {
+            let monkey = block
+                .parse::<Monkey>()
+                .map_err(|e| e.within(offset, block, block))?;
+            for (index, line) in block.lines().enumerate() {
+                if let Some(("If true" | "If false", statement)) = line.trim().split_once(':') {
+                    let word = statement.rsplit(' ').next().unwrap_or(statement);
+                    let error = |reason: String| {
+                        ParseError::new(Day11::DAY, reason).at(offset + index, line, word)
+                    };
+                    match word.parse::<usize>() {
+                        Ok(target) if target >= count => {
+                            return Err(error(format!(
+                                "No such monkey, expected one of 0 to {}",
+                                count - 1
+                            )))
+                        }
+                        Ok(target) if target == idx => {
+                            return Err(error("A monkey cannot throw to itself".to_string()))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            offset += block.lines().count() + 1;
+            Ok((idx, monkey))
+        })
+        .collect()
+}
+
+fn play_round(monkeys: &mut BTreeMap<usize, Monkey>, worry_unstable: bool) {
+    let re_mod: u64 = if worry_unstable {
+        // if x is divisible by d, it's also divisible by d * e. So we just use modulo product of all divisors. What's more, if x % d = y, then x % d * e = y as well.
+        monkeys.values().map(|m| m.divisor).product()
+    } else {
+        1
+    };
+
+    let keys = monkeys.keys().copied().collect::<Vec<_>>();
+    for idx in keys {
+        // Remove so the monkey can be mutated while the rest of the
+        // map is also mutated
+        let mut monkey = monkeys.remove(&idx).unwrap();
+
+        monkey.items.drain(..).for_each(|worry_level| {
+            let worry_level = if !worry_unstable {
+                operate(worry_level, monkey.operation) / 3
+            } else {
+                operate_re(worry_level, monkey.operation, re_mod)
+            };
+            let throw_index = match test_worry(worry_level, monkey.divisor) {
+                true => monkey.if_true,
+                false => monkey.if_false,
+            };
 
-    fn parse(input: &str) -> BTreeMap<usize, Monkey> {
-        input
-            .split("\n\n")
-            .enumerate()
-            .map(|(idx, monkey)| {
-                (
-                    idx,
-                    monkey.parse::<Monkey>().expect("Failed to create monkey"),
-                )
-            })
-            .collect()
+            monkeys
+                .get_mut(&throw_index)
+                .expect("Parsing checks every monkey throws to another")
+                .items
+                .push_back(worry_level);
+            monkey.num_inspections += 1;
+        });
+
+        monkeys.insert(idx, monkey);
     }
+}
 
-    fn play_round(monkeys: &mut BTreeMap<usize, Monkey>, worry_unstable: bool) {
-        let re_mod: u64 = if worry_unstable {
-            // if x is divisible by d, it's also divisible by d * e. So we just use modulo product of all divisors. What's more, if x % d = y, then x % d * e = y as well.
-            monkeys.values().map(|m| m.divisor).product()
-        } else {
-            1
-        };
+fn play(num_rounds: usize, monkeys: &mut BTreeMap<usize, Monkey>, worry_unstable: bool) {
+    for _ in 0..num_rounds {
+        play_round(monkeys, worry_unstable);
+    }
+}
 
-        let keys = monkeys.keys().copied().collect::<Vec<_>>();
-        for idx in keys {
-            // Remove so the monkey can be mutated while the rest of the
-            // map is also mutated
-            let mut monkey = monkeys.remove(&idx).unwrap();
-
-            monkey.items.drain(..).for_each(|worry_level| {
-                let worry_level = if !worry_unstable {
-                    operate(worry_level, monkey.operation) / 3
-                } else {
-                    operate_re(worry_level, monkey.operation, re_mod)
-                };
-                let throw_index = match test_worry(worry_level, monkey.divisor) {
-                    true => monkey.if_true,
-                    false => monkey.if_false,
-                };
-
-                monkeys
-                    .entry(throw_index)
-                    .and_modify(|monkey| monkey.items.push_back(worry_level));
-                monkey.num_inspections += 1;
-            });
-
-            monkeys.insert(idx, monkey);
-        }
+fn monkey_business(
+    monkeys: &BTreeMap<usize, Monkey>,
+    num_rounds: usize,
+    worry_unstable: bool,
+) -> usize {
+    let mut monkeys = monkeys.clone();
+    play(num_rounds, &mut monkeys, worry_unstable);
+    let mut num_inspections = monkeys
+        .into_values()
+        .map(|monkey| monkey.num_inspections)
+        .collect::<Vec<_>>();
+    num_inspections.sort();
+    num_inspections.into_iter().rev().take(2).product()
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = BTreeMap<usize, Monkey>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input.trim_end())
     }
 
-    fn play(num_rounds: usize, monkeys: &mut BTreeMap<usize, Monkey>, worry_unstable: bool) {
-        for _ in 0..num_rounds {
-            play_round(monkeys, worry_unstable);
-        }
+    fn part_one(monkeys: &Self::Input) -> Self::PartOne {
+        monkey_business(monkeys, 20, false)
+    }
+
+    fn part_two(monkeys: &Self::Input) -> Self::PartTwo {
+        monkey_business(monkeys, 10000, true)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_11_test_1() {
         let input = include_str!("test").trim();
-        let mut monkeys = parse(input);
-        play(20, &mut monkeys, false);
-        let mut num_inspections = monkeys
-            .into_values()
-            .map(|monkey| monkey.num_inspections)
-            .collect::<Vec<_>>();
-        num_inspections.sort();
-        let result: usize = num_inspections.into_iter().rev().take(2).product();
-
-        assert_eq!(result, 10605);
+        let monkeys = parse(input).unwrap();
+        let result = monkey_business(&monkeys, 20, false);
+
+        assert_answer(11, Part::One, "test", result);
     }
 
     #[test]
     fn day_11_challenge_1() {
         let input = include_str!("input").trim();
-        let mut monkeys = parse(input);
-        play(20, &mut monkeys, false);
-        let mut num_inspections = monkeys
-            .into_values()
-            .map(|monkey| monkey.num_inspections)
-            .collect::<Vec<_>>();
-        num_inspections.sort();
-        let result: usize = num_inspections.into_iter().rev().take(2).product();
-
-        assert_eq!(result, 90294);
+        let monkeys = parse(input).unwrap();
+        let result = monkey_business(&monkeys, 20, false);
+
+        assert_answer(11, Part::One, "input", result);
     }
 
     #[test]
     fn day_11_test_2() {
         let input = include_str!("test").trim();
-        let mut monkeys = parse(input);
-        play(10000, &mut monkeys, true);
-        let mut num_inspections = monkeys
-            .into_values()
-            .map(|monkey| monkey.num_inspections)
-            .collect::<Vec<_>>();
-        num_inspections.sort();
-        let result: usize = num_inspections.into_iter().rev().take(2).product();
-
-        assert_eq!(result, 2713310158);
+        let monkeys = parse(input).unwrap();
+        let result = monkey_business(&monkeys, 10000, true);
+
+        assert_answer(11, Part::Two, "test", result);
     }
 
     #[test]
     fn day_11_challenge_2() {
         let input = include_str!("input").trim();
-        let mut monkeys = parse(input);
-        play(10000, &mut monkeys, true);
-        let mut num_inspections = monkeys
-            .into_values()
-            .map(|monkey| monkey.num_inspections)
-            .collect::<Vec<_>>();
-        num_inspections.sort();
-        let result: usize = num_inspections.into_iter().rev().take(2).product();
-
-        assert_eq!(result, 18170818354);
+        let monkeys = parse(input).unwrap();
+        let result = monkey_business(&monkeys, 10000, true);
+
+        assert_answer(11, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_invalid_operation() {
+        let input = include_str!("test").replacen("old + 6", "old - 6", 1);
+        let error = parse(input.trim()).unwrap_err();
+        assert_eq!((error.line, error.column), (10, 24));
+        assert_eq!(error.text, "-");
+    }
+
+    #[test]
+    fn reports_missing_attributes_and_unknown_targets() {
+        let input = include_str!("test")
+            .trim()
+            .replacen("  Test: divisible by 23\n", "", 1);
+        let error = parse(&input).unwrap_err();
+        assert_eq!(
+            (error.line, error.reason.as_str()),
+            (1, "Missing `Test` line")
+        );
+        let input = include_str!("test").replacen("throw to monkey 3", "throw to monkey 4", 1);
+        let error = parse(input.trim()).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (6, 31, "4")
+        );
+        let input = include_str!("test").replacen("throw to monkey 2", "throw to monkey 0", 1);
+        let error = parse(input.trim()).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.reason.as_str()),
+            (5, 30, "A monkey cannot throw to itself")
+        );
     }
 }
@@ -1,201 +1,178 @@
-#[cfg(test)]
-mod tests {
-    use std::{
-        cmp::Ordering,
-        collections::{BTreeMap, BTreeSet},
-    };
-
-    #[derive(Copy, Clone, Debug)]
-    struct Coordinate {
-        x: usize,
-        y: usize,
-    }
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::render::{Cell, Frame, Render, Rgb};
+use crate::search::{bfs, Search};
+use crate::{ParseError, Solution};
+
+#[derive(Debug)]
+pub struct Map {
+    start: Point<usize>,
+    finish: Point<usize>,
+    heights: Grid<u8>,
+}
 
-    impl Ord for Coordinate {
-        fn cmp(&self, other: &Self) -> Ordering {
-            (self.x, self.y).cmp(&(other.x, other.y))
+fn parse(input: &str) -> Result<Map, ParseError> {
+    let mut start = None;
+    let mut finish = None;
+    let heights = Grid::parse(Day12::DAY, input, |position, ch| match ch {
+        'S' => {
+            start = Some(position);
+            Ok(1)
+        }
+        'E' => {
+            finish = Some(position);
+            Ok(26)
+        }
+        'a'..='z' => Ok(ch as u8 - 96),
+        _ => Err("Heights must be lowercase letters, `S` or `E`"),
+    })?;
+    let map = Map {
+        start: start.ok_or_else(|| ParseError::new(Day12::DAY, "No start `S` in the map"))?,
+        finish: finish.ok_or_else(|| ParseError::new(Day12::DAY, "No finish `E` in the map"))?,
+        heights,
+    };
+    // The start is one of the lowest points, so a route from it is a route for both parts
+    match map.shortest_path([map.start]) {
+        Some(_) => Ok(map),
+        None => {
+            let (index, line) = input.lines().enumerate().nth(map.finish.y).unwrap();
+            let token = &line[line.rfind('E').unwrap()..][..1];
+            Err(
+                ParseError::new(Day12::DAY, "The finish cannot be reached from the start")
+                    .at(index, line, token),
+            )
         }
     }
+}
 
-    impl PartialOrd for Coordinate {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            Some(self.cmp(other))
-        }
+impl Map {
+    /// Searches outwards from `starts`, climbing at most one unit per step,
+    /// until the finish is reached.
+    fn climb(&self, starts: impl IntoIterator<Item = Point<usize>>) -> Search<Point<usize>, usize> {
+        let climbable = |&position: &Point<usize>| {
+            let height = self.heights[position];
+            self.heights
+                .neighbours(position)
+                .filter(move |&next| height + 1 >= self.heights[next])
+        };
+        bfs(starts, climbable, |&position| position == self.finish)
     }
 
-    impl PartialEq for Coordinate {
-        fn eq(&self, other: &Self) -> bool {
-            (self.x == other.x) && (self.y == other.y)
-        }
+    /// The fewest steps from any of `starts` to the finish.
+    fn shortest_path(&self, starts: impl IntoIterator<Item = Point<usize>>) -> Option<usize> {
+        self.climb(starts).cost()
     }
+}
 
-    impl Eq for Coordinate {}
-
-    impl Coordinate {
-        fn neighbours(&self) -> impl Iterator<Item = Coordinate> {
-            [
-                (self.x as isize + 1, self.y as isize),
-                (self.x as isize - 1, self.y as isize),
-                (self.x as isize, self.y as isize + 1),
-                (self.x as isize, self.y as isize - 1),
-            ]
-            .into_iter()
-            .filter(|(x, y)| *x >= 0 && *y >= 0)
-            .map(|(x, y)| Self {
-                x: x as usize,
-                y: y as usize,
-            })
+/// Shades the map from low to high ground, tracing the shortest route
+/// from the start to the finish.
+impl Render for Map {
+    fn render(&self) -> Frame {
+        let mut frame = self.heights.map(|&height| {
+            Cell::new(
+                char::from(b'a' + height - 1),
+                Rgb::new(20, 60, 30).blend(Rgb::new(230, 230, 210), height as u32 - 1, 25),
+            )
+        });
+        let route = self.climb([self.start]).path().unwrap_or_default();
+        for position in route {
+            frame[position].colour = Rgb::new(255, 60, 60);
         }
+        frame[self.start].glyph = 'S';
+        frame[self.finish].glyph = 'E';
+        frame
     }
+}
 
-    #[derive(Debug)]
-    struct Map {
-        start: Coordinate,
-        finish: Coordinate,
-        heights: BTreeMap<Coordinate, u8>,
-    }
+fn fewest_steps_from_any_lowest(height_map: &Map) -> Option<usize> {
+    let lowest = height_map
+        .heights
+        .iter()
+        .filter(|(_, &height)| height == 1)
+        .map(|(position, _)| position);
+    height_map.shortest_path(lowest)
+}
 
-    #[derive(Copy, Clone, Debug)]
-    struct PathSegment(Coordinate, u16);
+pub struct Day12;
 
-    impl PartialEq for PathSegment {
-        fn eq(&self, other: &Self) -> bool {
-            self.1 == other.1
-        }
-    }
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    impl PartialOrd for PathSegment {
-        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-            use std::cmp::Reverse;
+    type Input = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-            Reverse(self.1).partial_cmp(&Reverse(other.1))
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input.trim_end())
     }
-    impl Eq for PathSegment {}
-    impl Ord for PathSegment {
-        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-            use std::cmp::Reverse;
 
-            Reverse(self.1).cmp(&Reverse(other.1))
-        }
+    fn part_one(height_map: &Self::Input) -> Self::PartOne {
+        height_map
+            .shortest_path([height_map.start])
+            .expect("Parsing checks the finish can be reached from the start")
     }
 
-    fn parse(input: &str) -> Map {
-        let mut start = None;
-        let mut finish = None;
-        let heights = input
-            .lines()
-            .enumerate()
-            .map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(|(x, ch)| match ch {
-                        'S' => {
-                            start = Some(Coordinate { x, y });
-                            (Coordinate { x, y }, 1)
-                        }
-                        'E' => {
-                            finish = Some(Coordinate { x, y });
-                            (Coordinate { x, y }, 26)
-                        }
-                        ch => (Coordinate { x, y }, ch as u8 - 96),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .flatten()
-            .collect();
-        Map {
-            start: start.unwrap(),
-            finish: finish.unwrap(),
-            heights,
-        }
+    fn part_two(height_map: &Self::Input) -> Self::PartTwo {
+        fewest_steps_from_any_lowest(height_map)
+            .expect("Parsing checks the finish can be reached from the start")
     }
 
-    impl Map {
-        fn shortest_path(&self, start: Coordinate) -> Option<u16> {
-            use std::collections::BinaryHeap;
-            let mut heap = BinaryHeap::from_iter([PathSegment(start, 0)].iter().copied());
-            let mut used: BTreeSet<Coordinate> = BTreeSet::new();
-            used.insert(start);
-
-            while !heap.is_empty() {
-                let PathSegment(coordinate, cost) = heap.pop().unwrap();
-
-                if coordinate == self.finish {
-                    return Some(cost);
-                }
-
-                let height = self
-                    .heights
-                    .get(&coordinate)
-                    .expect("Coordinate not found in heights");
-
-                for coordinate in coordinate.neighbours() {
-                    if !used.contains(&coordinate) {
-                        if let Some(&next_height) = self.heights.get(&coordinate) {
-                            if height + 1 >= next_height {
-                                used.insert(coordinate);
-                                heap.push(PathSegment(coordinate, cost + 1));
-                            }
-                        }
-                    }
-                }
-            }
-            None
-        }
+    fn render(height_map: &Self::Input) -> Option<Frame> {
+        Some(height_map.render())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_12_test_1() {
         let input = include_str!("test").trim();
 
-        let height_map = parse(input);
+        let height_map = parse(input).unwrap();
 
-        let result = height_map.shortest_path(height_map.start).unwrap();
+        let result = height_map.shortest_path([height_map.start]).unwrap();
 
-        assert_eq!(result, 31);
+        assert_answer(12, Part::One, "test", result);
     }
 
     #[test]
     fn day_12_challenge_1() {
         let input = include_str!("input").trim();
 
-        let height_map = parse(input);
+        let height_map = parse(input).unwrap();
 
-        let result = height_map.shortest_path(height_map.start).unwrap();
+        let result = height_map.shortest_path([height_map.start]).unwrap();
 
-        assert_eq!(result, 534);
+        assert_answer(12, Part::One, "input", result);
     }
 
     #[test]
     fn day_12_test_2() {
         let input = include_str!("test").trim();
-        let height_map = parse(input);
+        let height_map = parse(input).unwrap();
 
-        let result = height_map
-            .heights
-            .iter()
-            .filter(|(_, v)| **v == 1)
-            .flat_map(|(k, _)| height_map.shortest_path(*k))
-            .min()
-            .unwrap();
+        let result = fewest_steps_from_any_lowest(&height_map).unwrap();
 
-        assert_eq!(result, 29);
+        assert_answer(12, Part::Two, "test", result);
     }
 
     #[test]
     fn day_12_challenge_2() {
         let input = include_str!("input").trim();
-        let height_map = parse(input);
+        let height_map = parse(input).unwrap();
 
-        let result = height_map
-            .heights
-            .iter()
-            .filter(|(_, v)| **v == 1)
-            .flat_map(|(k, _)| height_map.shortest_path(*k))
-            .min()
-            .unwrap();
+        let result = fewest_steps_from_any_lowest(&height_map).unwrap();
 
-        assert_eq!(result, 525);
+        assert_answer(12, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn rejects_an_unreachable_finish() {
+        let error = parse("Sbz\nabE").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "E"));
     }
 }
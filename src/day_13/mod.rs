@@ -1,11 +1,11 @@
+use crate::{ParseError, Solution};
 use regex::Regex;
 use std::cmp::Ordering;
-use std::error::Error;
 
 /// A (possibly) nested list of integers.
 ///
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum NestedList {
+pub enum NestedList {
     Empty,
     Value(u16),
     List(Vec<NestedList>),
@@ -32,108 +32,139 @@ impl PartialOrd for NestedList {
 }
 
 impl NestedList {
-    fn decode(input: &str) -> Result<Vec<Self>, Box<dyn Error>> {
-        let tokens = Regex::new(r"[\[\]]|\d+")?;
+    fn decode(input: &str) -> Result<Vec<Self>, ParseError> {
+        // Every character is a token, so nothing in the input is skipped
+        let tokens = Regex::new(r"\d+|.").expect("Token pattern is valid");
         let mut lists = vec![];
-        let mut li_stack = vec![];
-        let mut li_curr = Self::Empty;
 
         macro_rules! invalid_input {
-            ($msg:expr) => {
-                return Err(format!("Invalid input - {}", $msg).into())
+            ($msg:expr, $index:expr, $line:expr, $token:expr) => {
+                return Err(
+                    ParseError::new(Day13::DAY, format!("Invalid input - {}", $msg))
+                        .at($index, $line, $token),
+                )
             };
         }
 
-        for line in input.lines() {
-            for token in tokens.captures_iter(&line) {
-                match &token[0] {
-                    "[" => {
-                        let list = Self::List(vec![]);
-                        match li_curr {
-                            Self::Empty => {
-                                li_curr = list;
-                            }
-                            Self::List(_) => {
-                                li_stack.push(li_curr);
-                                li_curr = list;
-                            }
-                            Self::Value(_) => {
-                                panic!("wrong variant for `li_curr`");
-                            }
-                        }
-                    }
-                    "]" => match li_curr {
-                        Self::List(_) => {
-                            if let Some(mut nl) = li_stack.pop() {
-                                if let Self::List(li) = &mut nl {
-                                    li.push(li_curr);
-                                    li_curr = nl;
-                                } else {
-                                    panic!("wrong variant on `li_stack`");
-                                }
-                            } else {
-                                lists.push(li_curr);
-                                li_curr = Self::Empty;
-                            }
-                        }
-                        _ => {
-                            panic!("wrong variant for `li_curr`");
+        for (index, line) in input.lines().enumerate() {
+            // Lists opened but not yet closed, innermost last
+            let mut li_stack: Vec<Vec<Self>> = vec![];
+            let mut packet = None;
+            // Whether the previous token ended a value or a list, so a `,`
+            // or `]` may follow but another value may not
+            let mut after_item = false;
+            for token in tokens.find_iter(line) {
+                let token = token.as_str();
+                if packet.is_some() && token != "]" {
+                    invalid_input!("expected one packet per line", index, line, token);
+                }
+                match token {
+                    "[" if after_item => invalid_input!("expected `,`", index, line, token),
+                    "[" => li_stack.push(vec![]),
+                    "]" => {
+                        let Some(li) = li_stack.pop() else {
+                            invalid_input!("unmatched `]`", index, line, token);
+                        };
+                        if !after_item && !li.is_empty() {
+                            invalid_input!("expected a value or `[`", index, line, token);
                         }
-                    },
-                    num => match &mut li_curr {
-                        Self::Empty => {
-                            invalid_input!("no list for value");
+                        match li_stack.last_mut() {
+                            Some(parent) => parent.push(Self::List(li)),
+                            None => packet = Some(Self::List(li)),
                         }
-                        Self::List(li) => {
-                            li.push(Self::Value(num.parse::<u16>()?));
+                        after_item = true;
+                    }
+                    "," if after_item && !li_stack.is_empty() => after_item = false,
+                    "," => invalid_input!("unexpected `,`", index, line, token),
+                    num if num.starts_with(|ch: char| ch.is_ascii_digit()) => {
+                        let Some(li) = li_stack.last_mut() else {
+                            invalid_input!("no list for value", index, line, num);
+                        };
+                        if after_item {
+                            invalid_input!("expected `,`", index, line, num);
                         }
-                        Self::Value(_) => {
-                            invalid_input!("attempt to push value to value");
+                        match num.parse::<u16>() {
+                            Ok(value) => li.push(Self::Value(value)),
+                            Err(_) => invalid_input!("value out of range", index, line, num),
                         }
-                    },
+                        after_item = true;
+                    }
+                    other => invalid_input!("unexpected character", index, line, other),
                 }
             }
+            if !li_stack.is_empty() {
+                invalid_input!("unclosed `[`", index, line, line);
+            }
+            lists.extend(packet);
         }
 
+        if lists.len() % 2 == 1 {
+            let (index, line) = input.lines().enumerate().last().unwrap_or((0, ""));
+            invalid_input!("the last packet has no pair", index, line, line);
+        }
         Ok(lists)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::NestedList;
-
-    fn part_1(decoded: Vec<NestedList>) -> usize {
-        decoded
-            .chunks(2)
-            .enumerate()
-            .fold(0, |sum, (idx, chunk)| match chunk[0] < chunk[1] {
-                true => sum + idx + 1,
-                false => sum,
-            })
-    }
+fn part_1(decoded: Vec<NestedList>) -> usize {
+    decoded
+        .chunks(2)
+        .enumerate()
+        .fold(0, |sum, (idx, chunk)| match chunk[0] < chunk[1] {
+            true => sum + idx + 1,
+            false => sum,
+        })
+}
 
-    fn part_2(mut decoded: Vec<NestedList>) -> usize {
-        let packet_1 = NestedList::List(vec![NestedList::Value(2)]);
-        let packet_2 = NestedList::List(vec![NestedList::Value(6)]);
+fn part_2(mut decoded: Vec<NestedList>) -> usize {
+    let packet_1 = NestedList::List(vec![NestedList::Value(2)]);
+    let packet_2 = NestedList::List(vec![NestedList::Value(6)]);
 
-        decoded.push(packet_1.clone());
-        decoded.push(packet_2.clone());
+    decoded.push(packet_1.clone());
+    decoded.push(packet_2.clone());
+
+    decoded.sort();
+
+    let position_1 = decoded.iter().position(|x| x.clone() == packet_1).unwrap() + 1;
+    let position_2 = decoded.iter().position(|x| x.clone() == packet_2).unwrap() + 1;
+
+    position_1 * position_2
+}
 
-        decoded.sort();
+pub struct Day13;
 
-        let position_1 = decoded.iter().position(|x| x.clone() == packet_1).unwrap() + 1;
-        let position_2 = decoded.iter().position(|x| x.clone() == packet_2).unwrap() + 1;
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-        position_1 * position_2
+    type Input = Vec<NestedList>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        NestedList::decode(input.trim_end())
+    }
+
+    fn part_one(decoded: &Self::Input) -> Self::PartOne {
+        part_1(decoded.clone())
+    }
+
+    fn part_two(decoded: &Self::Input) -> Self::PartTwo {
+        part_2(decoded.clone())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_13_test_1() {
         let input = include_str!("test").trim();
         let decoded = NestedList::decode(input).expect("Decode failed");
         let result = part_1(decoded);
-        assert_eq!(result, 13);
+        assert_answer(13, Part::One, "test", result);
     }
 
     #[test]
@@ -141,7 +172,7 @@ mod tests {
         let input = include_str!("input").trim();
         let decoded = NestedList::decode(input).expect("Decode failed");
         let result = part_1(decoded);
-        assert_eq!(result, 5852);
+        assert_answer(13, Part::One, "input", result);
     }
 
     #[test]
@@ -149,7 +180,7 @@ mod tests {
         let input = include_str!("test").trim();
         let decoded = NestedList::decode(input).expect("Decode failed");
         let result = part_2(decoded);
-        assert_eq!(result, 140);
+        assert_answer(13, Part::Two, "test", result);
     }
 
     #[test]
@@ -157,6 +188,29 @@ mod tests {
         let input = include_str!("input").trim();
         let decoded = NestedList::decode(input).expect("Decode failed");
         let result = part_2(decoded);
-        assert_eq!(result, 140);
+        assert_answer(13, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_unmatched_bracket() {
+        let error = NestedList::decode("[1,[2]]\n[3]]").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.text, "]");
+        assert_eq!(error.reason, "Invalid input - unmatched `]`");
+    }
+
+    #[test]
+    fn rejects_malformed_packets() {
+        let error = NestedList::decode("abc junk").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "a"));
+        let error = NestedList::decode("[1,x]\n[2]").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (4, "x"));
+        let error = NestedList::decode("[1,[2]\n[3]").unwrap_err();
+        assert_eq!(error.reason, "Invalid input - unclosed `[`");
+        let error = NestedList::decode("[1,,2]\n[3]").unwrap_err();
+        assert_eq!(error.column, 4);
+        let error = NestedList::decode("[1]\n[2]\n\n[3]").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (4, "[3]"));
+        assert_eq!(NestedList::decode("[[],1]\n[]").unwrap().len(), 2);
     }
 }
@@ -1,281 +1,217 @@
-use itertools::{EitherOrBoth::*, Itertools};
-use std::{cmp::Ordering, collections::BTreeSet, fmt::Display, str::FromStr};
-
-#[derive(Copy, Clone, Debug)]
-struct Coordinate {
-    x: usize,
-    y: usize,
+use std::collections::BTreeSet;
+
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::render::{Cell, Frame, Render, Rgb};
+use crate::{ParseError, Solution};
+
+/// Every point on the straight line from `start` to `finish`, inclusive.
+fn trace(start: Point<usize>, finish: Point<usize>) -> impl Iterator<Item = Point<usize>> {
+    std::iter::successors(Some(start), move |point| {
+        (*point != finish).then(|| point.step_towards(&finish))
+    })
 }
 
-impl Ord for Coordinate {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (self.y, self.x).cmp(&(other.y, other.x))
+fn decode(input: &str) -> Result<BTreeSet<Point<usize>>, ParseError> {
+    let mut rocks = BTreeSet::new();
+    for (index, line) in input.lines().enumerate() {
+        let endpoints = line
+            .split(" -> ")
+            .map(|c| Point::parse(Day14::DAY, c).map_err(|e| e.within(index, line, c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        rocks.extend(
+            endpoints
+                .windows(2)
+                .flat_map(|endpoints| trace(endpoints[0], endpoints[1])),
+        );
     }
+    Ok(rocks)
 }
 
-impl PartialOrd for Coordinate {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// Where sand enters the cave.
+const SOURCE: Point<usize> = Point::new(500, 0);
 
-impl PartialEq for Coordinate {
-    fn eq(&self, other: &Self) -> bool {
-        (self.x == other.x) && (self.y == other.y)
-    }
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Tile {
+    Air,
+    Rock,
+    Sand,
 }
 
-impl Eq for Coordinate {}
+/// A slice of the cave wide enough for sand to pile up to the source,
+/// with the floor two rows below the lowest rock.
+struct Cave {
+    tiles: Grid<Tile>,
+    /// The cave x coordinate of the first column of `tiles`.
+    left: usize,
+    lowest_rock: usize,
+}
 
-#[derive(Clone, Debug)]
-struct LineSegment {
-    start: Coordinate,
-    finish: Coordinate,
+impl Render for Cave {
+    fn render(&self) -> Frame {
+        self.tiles.map(|tile| match tile {
+            Tile::Air => Cell::new('\u{2591}', Rgb::new(30, 30, 40)),
+            Tile::Rock => Cell::new('\u{2593}', Rgb::new(130, 120, 110)),
+            Tile::Sand => Cell::new('*', Rgb::new(240, 200, 90)),
+        })
+    }
 }
 
-impl LineSegment {
-    fn from_coords(a: Coordinate, b: Coordinate) -> Self {
-        match a < b {
-            true => Self {
-                start: a,
-                finish: b,
-            },
-            false => Self {
-                start: b,
-                finish: a,
-            },
+impl Cave {
+    fn new(rocks: &BTreeSet<Point<usize>>) -> Self {
+        let lowest_rock = rocks.iter().map(|rock| rock.y).max().unwrap_or(0);
+        let floor = lowest_rock + 2;
+        // Sand can spread at most one column per row either side of the source
+        let left = rocks
+            .iter()
+            .map(|rock| rock.x)
+            .chain([SOURCE.x.saturating_sub(floor)])
+            .min()
+            .unwrap();
+        let right = rocks
+            .iter()
+            .map(|rock| rock.x)
+            .chain([SOURCE.x + floor])
+            .max()
+            .unwrap();
+        let mut tiles = Grid::new(right - left + 1, floor + 1, Tile::Air);
+        for rock in rocks {
+            tiles[Point::new(rock.x - left, rock.y)] = Tile::Rock;
+        }
+        for x in 0..tiles.width() {
+            tiles[Point::new(x, floor)] = Tile::Rock;
+        }
+        Self {
+            tiles,
+            left,
+            lowest_rock,
         }
     }
 
-    fn generate_coordinates(&self) -> Vec<Coordinate> {
-        (self.start.x..(self.finish.x + 1))
-            .zip_longest(self.start.y..(self.finish.y + 1))
-            .map(|pair| match pair {
-                Both(x, y) => Coordinate { x, y },
-                Left(x) => Coordinate { x, y: self.start.y },
-                Right(y) => Coordinate { x: self.start.x, y },
+    /// Drops one unit of sand from the source, returning where it comes to rest.
+    fn drop_sand(&mut self) -> Point<usize> {
+        let mut position = SOURCE - Point::new(self.left, 0);
+        while let Some(next) = [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)]
+            .into_iter()
+            .find_map(|delta| {
+                self.tiles
+                    .offset(position, delta)
+                    .filter(|&next| self.tiles[next] == Tile::Air)
             })
-            .collect()
+        {
+            position = next;
+        }
+        self.tiles[position] = Tile::Sand;
+        position
     }
 }
 
-impl FromStr for Coordinate {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split_once(',') {
-            Some((x, y)) => Ok(Self {
-                x: x.parse::<usize>().unwrap(),
-                y: y.parse::<usize>().unwrap(),
-            }),
-            _ => Err("Malformed input".to_string()),
+/// Pours sand into the cave until it starts falling into the abyss,
+/// returning the cave and how many units came to rest.
+fn fill_above_abyss(rocks: &BTreeSet<Point<usize>>) -> (Cave, usize) {
+    let mut cave = Cave::new(rocks);
+    let mut count = 0;
+    loop {
+        let position = cave.drop_sand();
+        // Sand resting below the lowest rock has fallen past every ledge into the abyss
+        if position.y > cave.lowest_rock {
+            cave.tiles[position] = Tile::Air;
+            return (cave, count);
         }
+        count += 1;
     }
 }
 
-fn decode(input: &str) -> BTreeSet<Coordinate> {
-    input
-        .lines()
-        .flat_map(|line| {
-            let endpoints = line
-                .split(" -> ")
-                .map(|c| c.parse::<Coordinate>().unwrap())
-                .collect::<Vec<_>>();
-            endpoints
-                .windows(2)
-                .map(|endpoints| LineSegment::from_coords(endpoints[0], endpoints[1]))
-                .flat_map(|line| line.generate_coordinates())
-                .collect::<Vec<_>>()
-        })
-        .collect()
+fn part_1(rocks: &BTreeSet<Point<usize>>) -> usize {
+    fill_above_abyss(rocks).1
 }
 
-struct Cave {
-    rocks: BTreeSet<Coordinate>,
-    sum: BTreeSet<Coordinate>,
-    sand: BTreeSet<Coordinate>,
+fn part_2(rocks: &BTreeSet<Point<usize>>) -> usize {
+    let mut cave = Cave::new(rocks);
+    let source = SOURCE - Point::new(cave.left, 0);
+    std::iter::repeat_with(|| cave.drop_sand())
+        .position(|position| position == source)
+        .expect("Sand always reaches the source")
+        + 1
 }
 
-impl Display for Cave {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let min_x = self.sum.iter().min_by(|a, b| a.x.cmp(&b.x)).unwrap().x;
-        let max_x = self.sum.iter().max_by(|a, b| a.x.cmp(&b.x)).unwrap().x;
-
-        let string = (self.sum.first().unwrap().y..(self.sum.last().unwrap().y + 1))
-            .map(|y| {
-                let mut figure = (min_x..(max_x + 1))
-                    .map(|x| {
-                        if let Some(_) = self.rocks.get(&Coordinate { x, y }) {
-                            '\u{2593}'
-                        } else if let Some(_) = self.sand.get(&Coordinate { x, y }) {
-                            '*'
-                        } else {
-                            '\u{2591}'
-                        }
-                    })
-                    .collect::<String>();
-                figure.push_str(format!("  {y}").as_str());
-                figure
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-        write!(f, "{}", string)
-    }
-}
+pub struct Day14;
 
-impl Cave {
-    fn is_outside(&self, position: Coordinate) -> bool {
-        let min_x = self.rocks.iter().min_by(|a, b| a.x.cmp(&b.x)).unwrap().x;
-        let max_x = self.rocks.iter().max_by(|a, b| a.x.cmp(&b.x)).unwrap().x;
-        let min_y = self.rocks.last().unwrap().y;
-
-        if (position.x < min_x) || (position.x > max_x) || (position.y > min_y) {
-            true
-        } else {
-            false
-        }
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = BTreeSet<Point<usize>>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        decode(input.trim_end())
     }
-    fn fall(&mut self, sand_position: Coordinate) -> bool {
-        let mut sand_position = Some(sand_position);
-        while let Some(position) = sand_position {
-            sand_position = self.fall_once(position);
-            if self.is_outside(position) {
-                return false;
-            }
-        }
-        true
+
+    fn part_one(rocks: &Self::Input) -> Self::PartOne {
+        part_1(rocks)
     }
 
-    fn fall_once(&mut self, sand_position: Coordinate) -> Option<Coordinate> {
-        if let Some(_) = self.sum.get(&Coordinate {
-            x: sand_position.x,
-            y: sand_position.y + 1,
-        }) {
-            if let Some(_) = self.sum.get(&Coordinate {
-                x: sand_position.x - 1,
-                y: sand_position.y + 1,
-            }) {
-                if let Some(_) = self.sum.get(&Coordinate {
-                    x: sand_position.x + 1,
-                    y: sand_position.y + 1,
-                }) {
-                    self.sand.insert(sand_position);
-                    self.sum.insert(sand_position);
-                    return None;
-                } else {
-                    return Some(Coordinate {
-                        x: sand_position.x + 1,
-                        y: sand_position.y + 1,
-                    });
-                }
-            } else {
-                return Some(Coordinate {
-                    x: sand_position.x - 1,
-                    y: sand_position.y + 1,
-                });
-            }
-        } else {
-            return Some(Coordinate {
-                x: sand_position.x,
-                y: sand_position.y + 1,
-            });
-        }
+    fn part_two(rocks: &Self::Input) -> Self::PartTwo {
+        part_2(rocks)
     }
 
-    fn fall_to_floor(&mut self, sand_position: Coordinate) -> bool {
-        let floor_row = self.rocks.last().unwrap().y + 2;
-        let mut sand_position = Some(sand_position);
-        let mut num_moves = 0;
-        while let Some(position) = sand_position {
-            sand_position = self.fall_once(position);
-            num_moves += 1;
-            if position.y == floor_row - 1 {
-                self.sand.insert(position);
-                self.sum.insert(position);
-                return true;
-            }
-        }
-        if num_moves == 1 {
-            false
-        } else {
-            true
-        }
+    /// Draws the sand that comes to rest in part one.
+    fn render(rocks: &Self::Input) -> Option<Frame> {
+        Some(fill_above_abyss(rocks).0.render())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn part_1(rocks: BTreeSet<Coordinate>) -> usize {
-        let mut cave = Cave {
-            rocks: rocks.clone(),
-            sum: rocks,
-            sand: BTreeSet::new(),
-        };
-        let fall_point = Coordinate { x: 500, y: 0 };
-
-        let mut n = 0;
-        while cave.fall(fall_point) {
-            n += 1;
-        }
-        // println!("{cave}");
-        n
-    }
-
-    fn part_2(rocks: BTreeSet<Coordinate>) -> usize {
-        let mut cave = Cave {
-            rocks: rocks.clone(),
-            sum: rocks,
-            sand: BTreeSet::new(),
-        };
-        let fall_point = Coordinate { x: 500, y: 0 };
-
-        let mut n = 1;
-        while cave.fall_to_floor(fall_point) {
-            n += 1;
-        }
-        // println!("{cave}");
-        n
-    }
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_14_test_1() {
         let input = include_str!("test").trim();
 
-        let rocks = decode(input);
-        let result = part_1(rocks);
+        let rocks = decode(input).unwrap();
+        let result = part_1(&rocks);
 
-        assert_eq!(result, 24);
+        assert_answer(14, Part::One, "test", result);
     }
 
     #[test]
     fn day_14_challenge_1() {
         let input = include_str!("input").trim();
 
-        let rocks = decode(input);
-        let result = part_1(rocks);
+        let rocks = decode(input).unwrap();
+        let result = part_1(&rocks);
 
-        assert_eq!(result, 832);
+        assert_answer(14, Part::One, "input", result);
     }
 
     #[test]
     fn day_14_test_2() {
         let input = include_str!("test").trim();
 
-        let rocks = decode(input);
-        let result = part_2(rocks);
+        let rocks = decode(input).unwrap();
+        let result = part_2(&rocks);
 
-        assert_eq!(result, 93);
+        assert_answer(14, Part::Two, "test", result);
     }
 
     #[test]
     fn day_14_challenge_2() {
         let input = include_str!("input").trim();
 
-        let rocks = decode(input);
-        let result = part_2(rocks);
+        let rocks = decode(input).unwrap();
+        let result = part_2(&rocks);
 
-        assert_eq!(result, 27601);
+        assert_answer(14, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_invalid_coordinate() {
+        let error = decode("498,4 -> 498,6 -> 496,6\n503,4 -> 502,x").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 14));
+        assert_eq!(error.text, "x");
     }
 }
@@ -0,0 +1,254 @@
+use std::fmt::{self, Display};
+
+use super::rules::Rules;
+use super::{Game, Guess, Outcome};
+
+/// How one round of the strategy guide was scored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Guess,
+    pub mine: Guess,
+    pub outcome: Outcome,
+    /// Points for the shape we played
+    pub shape_points: usize,
+    /// Points for how the round ended
+    pub outcome_points: usize,
+}
+
+impl Round {
+    pub fn total(&self) -> usize {
+        self.shape_points + self.outcome_points
+    }
+}
+
+/// Every round of a strategy guide with its score, and totals over them all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ledger {
+    pub rounds: Vec<Round>,
+    /// Points from the shapes played, summed over every round
+    pub shape_points: usize,
+    /// Points from the outcomes, summed over every round
+    pub outcome_points: usize,
+    /// Rounds with each outcome, indexed by [`Outcome`]
+    counts: [usize; 3],
+    /// Longest run of consecutive rounds with each outcome
+    streaks: [usize; 3],
+    /// Rounds we played each shape, indexed by [`Guess`]
+    plays: Vec<usize>,
+    /// Shape points from each shape, indexed by [`Guess`]
+    points: Vec<usize>,
+    /// For naming the shapes in the table
+    rules: Rules,
+}
+
+impl Ledger {
+    /// Scores each of `games` under `rules`.
+    pub fn new(games: &[Game], rules: &Rules) -> Self {
+        let rounds = games
+            .iter()
+            .map(|game| {
+                let outcome = rules.outcome(game.my_choice, game.opponent_choice);
+                Round {
+                    opponent: game.opponent_choice,
+                    mine: game.my_choice,
+                    outcome,
+                    shape_points: game.my_choice.score(rules),
+                    outcome_points: rules.outcome_points(outcome),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut counts = [0; 3];
+        let mut streaks = [0; 3];
+        let mut streak = 0;
+        for (i, round) in rounds.iter().enumerate() {
+            let outcome = round.outcome as usize;
+            counts[outcome] += 1;
+            match i.checked_sub(1).map(|last| rounds[last].outcome) {
+                Some(last) if last == round.outcome => streak += 1,
+                _ => streak = 1,
+            }
+            streaks[outcome] = streaks[outcome].max(streak);
+        }
+
+        let mut plays = vec![0; rules.len()];
+        let mut points = vec![0; rules.len()];
+        for round in &rounds {
+            plays[round.mine.0] += 1;
+            points[round.mine.0] += round.shape_points;
+        }
+
+        Self {
+            shape_points: rounds.iter().map(|round| round.shape_points).sum(),
+            outcome_points: rounds.iter().map(|round| round.outcome_points).sum(),
+            rounds,
+            counts,
+            streaks,
+            plays,
+            points,
+            rules: rules.clone(),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.shape_points + self.outcome_points
+    }
+
+    /// Number of rounds ending with `outcome`.
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.counts[outcome as usize]
+    }
+
+    /// Most consecutive rounds ending with `outcome`.
+    pub fn longest_streak(&self, outcome: Outcome) -> usize {
+        self.streaks[outcome as usize]
+    }
+
+    /// Number of rounds in which we played `shape`.
+    pub fn plays(&self, shape: Guess) -> usize {
+        self.plays[shape.0]
+    }
+
+    /// Points from the rounds in which we played `shape`, for the shape alone.
+    pub fn shape_points_for(&self, shape: Guess) -> usize {
+        self.points[shape.0]
+    }
+}
+
+impl Display for Ledger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |weapon| self.rules.name(weapon);
+        let width = self
+            .rules
+            .weapons()
+            .map(|weapon| name(weapon).len())
+            .max()
+            .unwrap_or(0)
+            .max("Opponent".len());
+        let number = self.rounds.len().to_string().len().max("Round".len());
+
+        writeln!(
+            f,
+            "{:>number$}  {:<width$}  {:<width$}  {:<7}  {:>5}  {:>7}  {:>5}",
+            "Round", "Opponent", "Mine", "Outcome", "Shape", "Outcome", "Total"
+        )?;
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>number$}  {:<width$}  {:<width$}  {:<7}  {:>5}  {:>7}  {:>5}",
+                i + 1,
+                name(round.opponent),
+                name(round.mine),
+                round.outcome,
+                round.shape_points,
+                round.outcome_points,
+                round.total()
+            )?;
+        }
+        writeln!(
+            f,
+            "{:>number$}  {:<width$}  {:<width$}  {:<7}  {:>5}  {:>7}  {:>5}",
+            "Total",
+            "",
+            "",
+            "",
+            self.shape_points,
+            self.outcome_points,
+            self.total()
+        )?;
+
+        write!(
+            f,
+            "\n{:<7}  {:>6}  {:>14}",
+            "Outcome", "Rounds", "Longest streak"
+        )?;
+        for outcome in [Outcome::Win, Outcome::Draw, Outcome::Lose] {
+            write!(
+                f,
+                "\n{outcome:<7}  {:>6}  {:>14}",
+                self.count(outcome),
+                self.longest_streak(outcome)
+            )?;
+        }
+
+        write!(
+            f,
+            "\n\n{:<width$}  {:>6}  {:>6}",
+            "Shape", "Rounds", "Points"
+        )?;
+        for shape in self.rules.weapons() {
+            write!(
+                f,
+                "\n{:<width$}  {:>6}  {:>6}",
+                name(shape),
+                self.plays(shape),
+                self.shape_points_for(shape)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_2::{parse, Interpretation};
+
+    #[test]
+    fn breaks_down_each_round() {
+        let games = parse("A Y\nB X\nC Z", Interpretation::Shape).unwrap();
+        let ledger = Ledger::new(&games, &Rules::standard());
+        assert_eq!(
+            ledger.rounds[0],
+            Round {
+                opponent: Guess::ROCK,
+                mine: Guess::PAPER,
+                outcome: Outcome::Win,
+                shape_points: 2,
+                outcome_points: 6,
+            }
+        );
+        assert_eq!((ledger.shape_points, ledger.outcome_points), (6, 9));
+        assert_eq!(ledger.total(), 15);
+        let table = ledger.to_string();
+        assert!(table.contains("    2  Paper     Rock      Lose         1        0      1"));
+        assert!(table.contains("Total                                   6        9     15"));
+        assert!(table.ends_with("Scissors       1       3"));
+    }
+
+    #[test]
+    fn breaks_down_each_shape() {
+        let games = parse("A Y\nB X\nC Y\nA Y", Interpretation::Shape).unwrap();
+        let ledger = Ledger::new(&games, &Rules::standard());
+        assert_eq!(ledger.plays(Guess::PAPER), 3);
+        assert_eq!(ledger.shape_points_for(Guess::PAPER), 6);
+        assert_eq!(ledger.plays(Guess::SCISSORS), 0);
+        let total = [Guess::ROCK, Guess::PAPER, Guess::SCISSORS]
+            .map(|shape| ledger.shape_points_for(shape))
+            .iter()
+            .sum::<usize>();
+        assert_eq!(total, ledger.shape_points);
+        assert!(ledger.to_string().contains("\nPaper          3       6"));
+    }
+
+    #[test]
+    fn counts_outcomes_and_streaks() {
+        let games = parse(
+            "A Y\nA Y\nA X\nA Z\nA Z\nA Y\nA Y\nA Y",
+            Interpretation::Outcome,
+        )
+        .unwrap();
+        let ledger = Ledger::new(&games, &Rules::standard());
+        assert_eq!(ledger.count(Outcome::Draw), 5);
+        assert_eq!(ledger.count(Outcome::Win), 2);
+        assert_eq!(ledger.count(Outcome::Lose), 1);
+        assert_eq!(ledger.longest_streak(Outcome::Draw), 3);
+        assert_eq!(ledger.longest_streak(Outcome::Win), 2);
+        assert_eq!(ledger.longest_streak(Outcome::Lose), 1);
+        let scores = games
+            .iter()
+            .map(|game| game.score_game(&Rules::standard()))
+            .sum::<usize>();
+        assert_eq!(ledger.total(), scores);
+    }
+}
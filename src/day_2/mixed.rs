@@ -0,0 +1,287 @@
+use super::rules::Rules;
+use super::{Game, Guess};
+use crate::simplex::{self, EPSILON};
+
+/// A pair of mixed strategies where neither player can score more by
+/// changing theirs alone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Equilibrium {
+    /// Probability of playing each weapon, in the order of the rules
+    pub mine: Vec<f64>,
+    pub theirs: Vec<f64>,
+    /// Our expected score per round
+    pub my_score: f64,
+    /// The opponent's expected score per round
+    pub their_score: f64,
+}
+
+/// Expected scores when both players pick weapons at random, under any
+/// weapons and weights described by a set of [`Rules`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    /// Our points for each pair of weapons, indexed by ours then theirs.
+    /// The game is symmetric, so the opponent's points are the transpose.
+    pub payoff: Vec<Vec<f64>>,
+}
+
+impl Analysis {
+    pub fn new(rules: &Rules) -> Self {
+        let payoff = rules
+            .weapons()
+            .map(|mine| {
+                rules
+                    .weapons()
+                    .map(|theirs| {
+                        let game = Game {
+                            my_choice: mine,
+                            opponent_choice: theirs,
+                        };
+                        game.score_game(rules) as f64
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { payoff }
+    }
+
+    /// Our expected score per round when we play each weapon with the
+    /// probabilities in `mine`, and the opponent with those in `theirs`.
+    pub fn expected(&self, mine: &[f64], theirs: &[f64]) -> f64 {
+        expected(&self.payoff, mine, theirs)
+    }
+
+    /// The weapon scoring most on average against `theirs`, and its score.
+    pub fn best_response(&self, theirs: &[f64]) -> (Guess, f64) {
+        self.payoff
+            .iter()
+            .map(|row| dot(row, theirs))
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(weapon, score)| (Guess(weapon), score))
+            .unwrap()
+    }
+
+    /// The equilibrium when each player tries to beat the other's score,
+    /// rather than maximise their own: a zero-sum game over the margin.
+    pub fn competitive(&self) -> Equilibrium {
+        let margin = (0..self.payoff.len())
+            .map(|i| {
+                (0..self.payoff.len())
+                    .map(|j| self.payoff[i][j] - self.payoff[j][i])
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        let (mine, theirs, _) = zero_sum(&margin);
+        self.equilibrium(mine, theirs)
+    }
+
+    /// Every equilibrium when each player maximises their own score, found
+    /// by enumerating pairs of supports of equal size.
+    ///
+    /// Degenerate games can have further equilibria, with supports of
+    /// different sizes, which this misses.
+    pub fn equilibria(&self) -> Vec<Equilibrium> {
+        let theirs = transpose(&self.payoff);
+        bimatrix(&self.payoff, &theirs)
+            .into_iter()
+            .map(|(mine, theirs)| self.equilibrium(mine, theirs))
+            .collect()
+    }
+
+    fn equilibrium(&self, mine: Vec<f64>, theirs: Vec<f64>) -> Equilibrium {
+        Equilibrium {
+            my_score: self.expected(&mine, &theirs),
+            their_score: self.expected(&theirs, &mine),
+            mine,
+            theirs,
+        }
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn transpose(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    (0..matrix.first().map_or(0, Vec::len))
+        .map(|j| matrix.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+/// The row player's expected payoff from `matrix` under the given mixes.
+pub fn expected(matrix: &[Vec<f64>], rows: &[f64], columns: &[f64]) -> f64 {
+    matrix
+        .iter()
+        .zip(rows)
+        .map(|(row, p)| p * dot(row, columns))
+        .sum()
+}
+
+/// Optimal mixes for a zero-sum game where the row player receives
+/// `matrix[i][j]` from the column player, and the value of the game.
+pub fn zero_sum(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<f64>, f64) {
+    // Shift every payoff to be positive, so the value is too. The column
+    // player's program, max Σy subject to Ay <= 1, is then feasible at the
+    // origin and bounded, and its duals give the row player's mix.
+    let lowest = matrix
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let shift = 1.0 - lowest;
+    let shifted = matrix
+        .iter()
+        .map(|row| row.iter().map(|value| value + shift).collect())
+        .collect::<Vec<_>>();
+    let columns = matrix.first().map_or(0, Vec::len);
+    let optimum = simplex::maximise(&vec![1.0; columns], &shifted, &vec![1.0; matrix.len()])
+        .expect("a game with positive payoffs is bounded");
+    let scale = |mix: Vec<f64>| mix.into_iter().map(|p| p / optimum.value).collect();
+    (
+        scale(optimum.dual),
+        scale(optimum.x),
+        1.0 / optimum.value - shift,
+    )
+}
+
+/// Equilibria of the game where the row player receives `a[i][j]` and the
+/// column player `b[i][j]`, with supports of equal size.
+pub fn bimatrix(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<(Vec<f64>, Vec<f64>)> {
+    let (m, n) = (a.len(), a.first().map_or(0, Vec::len));
+    let supports = |size: usize| {
+        (1..1usize << size)
+            .map(move |mask| (0..size).filter(|i| mask >> i & 1 == 1).collect::<Vec<_>>())
+    };
+    let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-6);
+
+    let mut found: Vec<(Vec<f64>, Vec<f64>)> = vec![];
+    for rows in supports(m) {
+        for columns in supports(n).filter(|columns| columns.len() == rows.len()) {
+            // The column mix must leave the row player indifferent between
+            // the rows in their support, and vice versa.
+            let Some(y) = indifferent(a, &rows, &columns, n) else {
+                continue;
+            };
+            let Some(x) = indifferent(&transpose(b), &columns, &rows, m) else {
+                continue;
+            };
+            let best = |matrix: &[Vec<f64>], mix: &[f64], support: &[usize]| {
+                let payoffs = matrix.iter().map(|row| dot(row, mix)).collect::<Vec<_>>();
+                let value = payoffs[support[0]];
+                payoffs.iter().all(|&payoff| payoff <= value + EPSILON)
+            };
+            if best(a, &y, &rows)
+                && best(&transpose(b), &x, &columns)
+                && !found.iter().any(|(fx, fy)| close(fx, &x) && close(fy, &y))
+            {
+                found.push((x, y));
+            }
+        }
+    }
+    found
+}
+
+/// A mix over `width` strategies, supported on `columns`, that makes every
+/// row in `rows` of `matrix` pay the same, if one exists.
+fn indifferent(
+    matrix: &[Vec<f64>],
+    rows: &[usize],
+    columns: &[usize],
+    width: usize,
+) -> Option<Vec<f64>> {
+    // Unknowns are the probabilities of `columns` then the common payoff
+    let k = columns.len();
+    let mut system = rows
+        .iter()
+        .map(|&row| {
+            let mut equation = columns
+                .iter()
+                .map(|&column| matrix[row][column])
+                .collect::<Vec<_>>();
+            equation.push(-1.0);
+            equation
+        })
+        .collect::<Vec<_>>();
+    system.push([vec![1.0; k], vec![0.0]].concat());
+    let mut constants = vec![0.0; k];
+    constants.push(1.0);
+
+    let solution = simplex::solve(system, constants)?;
+    if solution[..k].iter().any(|&p| p < -EPSILON) {
+        return None;
+    }
+    let mut mix = vec![0.0; width];
+    for (&column, &p) in columns.iter().zip(&solution) {
+        mix[column] = p.max(0.0);
+    }
+    Some(mix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f64], b: &[f64]) -> bool {
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-6)
+    }
+
+    #[test]
+    fn scores_mixed_strategies() {
+        let analysis = Analysis::new(&Rules::standard());
+        let uniform = [1.0 / 3.0; 3];
+        assert!((analysis.expected(&uniform, &uniform) - 5.0).abs() < 1e-9);
+        // Always Rock is beaten by Paper, for 8 points
+        assert_eq!(
+            analysis.best_response(&[1.0, 0.0, 0.0]),
+            (Guess::PAPER, 8.0)
+        );
+    }
+
+    #[test]
+    fn finds_competitive_equilibrium() {
+        // Paper beats Rock and Scissors beats Paper by 7 points more than
+        // the loser scores, but Rock beats Scissors by only 4
+        let equilibrium = Analysis::new(&Rules::standard()).competitive();
+        let expected = [7.0 / 18.0, 4.0 / 18.0, 7.0 / 18.0];
+        assert!(close(&equilibrium.mine, &expected));
+        assert!(close(&equilibrium.theirs, &expected));
+        assert!((equilibrium.my_score - equilibrium.their_score).abs() < 1e-9);
+    }
+
+    #[test]
+    fn finds_equilibria_of_own_scores() {
+        let equilibria = Analysis::new(&Rules::standard()).equilibria();
+        assert_eq!(equilibria.len(), 1);
+        let expected = [4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0];
+        assert!(close(&equilibria[0].mine, &expected));
+        assert!(close(&equilibria[0].theirs, &expected));
+        assert!((equilibria[0].my_score - 5.0).abs() < 1e-9);
+
+        // With equal shape scores, play is uniform again
+        let rules = Rules::new(
+            &["Rock", "Paper", "Scissors"],
+            &[
+                ("Paper", "Rock"),
+                ("Scissors", "Paper"),
+                ("Rock", "Scissors"),
+            ],
+            &[0, 0, 0],
+        )
+        .unwrap()
+        .with_outcome_points(0, 1, 2);
+        let equilibria = Analysis::new(&rules).equilibria();
+        assert!(close(&equilibria[0].mine, &[1.0 / 3.0; 3]));
+    }
+
+    #[test]
+    fn solves_zero_sum_games() {
+        // Matching pennies, shifted so the value is 1
+        let (rows, columns, value) = zero_sum(&[vec![2.0, 0.0], vec![0.0, 2.0]]);
+        assert!(close(&rows, &[0.5, 0.5]) && close(&columns, &[0.5, 0.5]));
+        assert!((value - 1.0).abs() < 1e-9);
+        // A dominant row is played outright
+        let (rows, _, value) = zero_sum(&[vec![3.0, 4.0], vec![1.0, 2.0]]);
+        assert!(close(&rows, &[1.0, 0.0]));
+        assert!((value - 3.0).abs() < 1e-9);
+    }
+}
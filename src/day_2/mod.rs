@@ -1,138 +1,308 @@
-use std::cmp::Ordering;
-
-#[derive(Clone, PartialEq, Eq)]
-enum Guess {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl Ord for Guess {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Guess::Rock, Guess::Rock) => Ordering::Equal,
-            (Guess::Rock, Guess::Paper) => Ordering::Less,
-            (Guess::Rock, Guess::Scissors) => Ordering::Greater,
-            (Guess::Paper, Guess::Rock) => Ordering::Greater,
-            (Guess::Paper, Guess::Paper) => Ordering::Equal,
-            (Guess::Paper, Guess::Scissors) => Ordering::Less,
-            (Guess::Scissors, Guess::Rock) => Ordering::Less,
-            (Guess::Scissors, Guess::Paper) => Ordering::Greater,
-            (Guess::Scissors, Guess::Scissors) => Ordering::Equal,
-        }
+pub mod ledger;
+pub mod mixed;
+pub mod optimise;
+pub mod rules;
+
+use std::fmt::{self, Display};
+
+use crate::{ParseError, Solution};
+use rules::Rules;
+
+/// A weapon, identified by its position in the [`Rules`] of the game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Guess(pub usize);
+
+impl Guess {
+    /// Weapons of the [standard](Rules::standard) game.
+    pub const ROCK: Self = Self(0);
+    pub const PAPER: Self = Self(1);
+    pub const SCISSORS: Self = Self(2);
+
+    pub fn score(&self, rules: &Rules) -> usize {
+        rules.score(*self)
     }
 }
 
-impl PartialOrd for Guess {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub my_choice: Guess,
+    pub opponent_choice: Guess,
+}
+
+impl Game {
+    pub fn score_game(&self, rules: &Rules) -> usize {
+        let outcome = rules.outcome(self.my_choice, self.opponent_choice);
+        rules.outcome_points(outcome) + self.my_choice.score(rules)
     }
 }
 
-impl Guess {
-    fn score(&self) -> usize {
+/// The result of a round, from our point of view.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Lose => f.pad("Lose"),
+            Outcome::Draw => f.pad("Draw"),
+            Outcome::Win => f.pad("Win"),
+        }
+    }
+}
+
+/// What a letter in the second column of the strategy guide tells us.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The shape to play
+    Shape(Guess),
+    /// How the round should end
+    Outcome(Outcome),
+}
+
+impl Response {
+    /// The shape to play against `opponent`, or `None` if no shape gives
+    /// the outcome asked for.
+    pub fn choose(&self, opponent: Guess, rules: &Rules) -> Option<Guess> {
         match *self {
-            Guess::Rock => 1,
-            Guess::Paper => 2,
-            Guess::Scissors => 3,
+            Response::Shape(shape) => Some(shape),
+            Response::Outcome(outcome) => rules.respond(opponent, outcome),
         }
     }
 }
 
-struct Game {
-    my_choice: Guess,
-    opponent_choice: Guess,
+/// How the second column of the strategy guide is read.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interpretation {
+    /// `X`, `Y` and `Z` mean Rock, Paper and Scissors.
+    Shape,
+    /// `X`, `Y` and `Z` mean lose, draw and win.
+    Outcome,
+    /// The responses for `X`, `Y` and `Z`, in that order.
+    Custom([Response; 3]),
 }
 
-impl Game {
-    fn score_game(&self) -> usize {
-        match self.my_choice.cmp(&self.opponent_choice) {
-            Ordering::Greater => 6 + self.my_choice.score(),
-            Ordering::Equal => 3 + self.my_choice.score(),
-            Ordering::Less => self.my_choice.score(),
+impl Interpretation {
+    /// The responses for `X`, `Y` and `Z`, in that order.
+    pub fn responses(&self) -> [Response; 3] {
+        match self {
+            Interpretation::Shape => {
+                [Guess::ROCK, Guess::PAPER, Guess::SCISSORS].map(Response::Shape)
+            }
+            Interpretation::Outcome => {
+                [Outcome::Lose, Outcome::Draw, Outcome::Win].map(Response::Outcome)
+            }
+            Interpretation::Custom(responses) => *responses,
+        }
+    }
+
+    /// The letters of the standard guide: `A`, `B` and `C` for the
+    /// opponent's shapes, and `X`, `Y` and `Z` read as `self`.
+    pub fn key(&self) -> Key {
+        Key {
+            opponent: vec![
+                ('A', Guess::ROCK),
+                ('B', Guess::PAPER),
+                ('C', Guess::SCISSORS),
+            ],
+            response: ['X', 'Y', 'Z'].into_iter().zip(self.responses()).collect(),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// What each letter of a strategy guide stands for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Key {
+    /// Letters of the first column and the opponent's weapon for each
+    pub opponent: Vec<(char, Guess)>,
+    /// Letters of the second column and what each tells us
+    pub response: Vec<(char, Response)>,
+}
+
+/// The value for the single letter `token` in `table`.
+fn lookup<T: Copy>(table: &[(char, T)], token: &str) -> Option<T> {
+    let mut chars = token.chars();
+    let letter = chars.next().filter(|_| chars.next().is_none())?;
+    table
+        .iter()
+        .find(|(key, _)| *key == letter)
+        .map(|&(_, value)| value)
+}
+
+/// Lists the letters of `table` for an error message, e.g. "`A`, `B` or `C`".
+fn expected<T>(table: &[(char, T)]) -> String {
+    let letters = table
+        .iter()
+        .map(|(letter, _)| format!("`{letter}`"))
+        .collect::<Vec<_>>();
+    match letters.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("Expected {} or {last}", rest.join(", ")),
+        Some((last, _)) => format!("Expected {last}"),
+        None => "Expected no rounds".into(),
+    }
+}
+
+/// Reads the standard strategy guide, with the second column read
+/// according to `interpretation`.
+pub fn parse(input: &str, interpretation: Interpretation) -> Result<Vec<Game>, ParseError> {
+    parse_with(input, &Rules::standard(), &interpretation.key())
+}
 
-    fn parse_challenge_1(input: &str) -> Vec<Game> {
-        input
-            .split('\n')
-            .map(|game| {
-                let game = game.split(' ').collect::<Vec<_>>();
-                assert_eq!(game.len(), 2);
-                let opponent_choice = match game[0] {
-                    "A" => Guess::Rock,
-                    "B" => Guess::Paper,
-                    "C" => Guess::Scissors,
-                    _ => unreachable!(),
-                };
-                let my_choice = match game[1] {
-                    "X" => Guess::Rock,
-                    "Y" => Guess::Paper,
-                    "Z" => Guess::Scissors,
-                    _ => unreachable!(),
-                };
-                Game {
-                    my_choice,
-                    opponent_choice,
-                }
+/// Reads a strategy guide for any game, with letters read using `key`.
+pub fn parse_with(input: &str, rules: &Rules, key: &Key) -> Result<Vec<Game>, ParseError> {
+    input
+        .trim_end()
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let error = |reason: &str, token: &str| {
+                ParseError::new(Day2::DAY, reason).at(index, line, token)
+            };
+            let (opponent, response) = line
+                .split_once(' ')
+                .ok_or_else(|| error("Expected two space-separated columns", line))?;
+            // The key may name weapons beyond those of `rules`
+            let known = |weapon: Guess, token| {
+                (weapon.0 < rules.len()).then_some(weapon).ok_or_else(|| {
+                    error(
+                        &format!("No such weapon, the game has {}", rules.len()),
+                        token,
+                    )
+                })
+            };
+            let opponent_choice = lookup(&key.opponent, opponent)
+                .ok_or_else(|| error(&expected(&key.opponent), opponent))?;
+            let opponent_choice = known(opponent_choice, opponent)?;
+            let my_choice = lookup(&key.response, response)
+                .ok_or_else(|| error(&expected(&key.response), response))?
+                .choose(opponent_choice, rules)
+                .ok_or_else(|| error("No shape gives this outcome", response))?;
+            let my_choice = known(my_choice, response)?;
+            Ok(Game {
+                my_choice,
+                opponent_choice,
             })
-            .collect()
+        })
+        .collect()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    /// The strategy guide, read with the second column as a shape and as an outcome.
+    type Input = (Vec<Game>, Vec<Game>);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((
+            parse(input, Interpretation::Shape)?,
+            parse(input, Interpretation::Outcome)?,
+        ))
     }
 
+    fn part_one((games, _): &Self::Input) -> Self::PartOne {
+        let rules = Rules::standard();
+        games.iter().map(|game| game.score_game(&rules)).sum()
+    }
+
+    fn part_two((_, games): &Self::Input) -> Self::PartTwo {
+        let rules = Rules::standard();
+        games.iter().map(|game| game.score_game(&rules)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
     use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn challenge_1() {
-        let input = include_str!("input").trim();
-        let games = parse_challenge_1(input);
-        let result = games.into_iter().fold(0, |a, b| a + b.score_game());
-        assert_eq!(result, 9651);
-    }
-
-    fn parse_challenge_2(input: &str) -> Vec<Game> {
-        input
-            .split('\n')
-            .map(|game| {
-                let game = game.split(' ').collect::<Vec<_>>();
-                assert_eq!(game.len(), 2);
-                let opponent_choice = match game[0] {
-                    "A" => Guess::Rock,
-                    "B" => Guess::Paper,
-                    "C" => Guess::Scissors,
-                    _ => unreachable!(),
-                };
-
-                let my_choice = match game[1] {
-                    "X" => match opponent_choice {
-                        Guess::Rock => Guess::Scissors,
-                        Guess::Paper => Guess::Rock,
-                        Guess::Scissors => Guess::Paper,
-                    },
-                    "Y" => opponent_choice.clone(),
-                    "Z" => match opponent_choice {
-                        Guess::Rock => Guess::Paper,
-                        Guess::Paper => Guess::Scissors,
-                        Guess::Scissors => Guess::Rock,
-                    },
-                    _ => unreachable!(),
-                };
-                Game {
-                    my_choice,
-                    opponent_choice,
-                }
-            })
-            .collect()
+        let games = parse(include_str!("input"), Interpretation::Shape).unwrap();
+        let rules = Rules::standard();
+        let result = games.iter().fold(0, |a, b| a + b.score_game(&rules));
+        assert_answer(2, Part::One, "input", result);
     }
 
     #[test]
     fn challenge_2() {
-        let input = include_str!("input").trim();
-        let games = parse_challenge_2(input);
-        let result = games.into_iter().fold(0, |a, b| a + b.score_game());
-        assert_eq!(result, 10560);
+        let games = parse(include_str!("input"), Interpretation::Outcome).unwrap();
+        let rules = Rules::standard();
+        let result = games.iter().fold(0, |a, b| a + b.score_game(&rules));
+        assert_answer(2, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_unknown_shape() {
+        let error = parse("A Y\nB X\nD Z", Interpretation::Shape).unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.text, "D");
+        let error = parse("A Y\nB W", Interpretation::Outcome).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
+    #[test]
+    fn reads_custom_mapping() {
+        // Always play Paper for `X`, and read `Y` and `Z` as outcomes
+        let interpretation = Interpretation::Custom([
+            Response::Shape(Guess::PAPER),
+            Response::Outcome(Outcome::Draw),
+            Response::Outcome(Outcome::Win),
+        ]);
+        let games = parse("A X\nB Y\nC Z\n", interpretation).unwrap();
+        let mine = games.iter().map(|game| game.my_choice).collect::<Vec<_>>();
+        assert_eq!(mine, [Guess::PAPER, Guess::PAPER, Guess::ROCK]);
+        let rules = Rules::standard();
+        let total = games
+            .iter()
+            .map(|game| game.score_game(&rules))
+            .sum::<usize>();
+        assert_eq!(total, 8 + 5 + 7);
+    }
+
+    #[test]
+    fn plays_games_with_more_weapons() {
+        let rules = Rules::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"])
+            .unwrap()
+            .with_outcome_points(0, 1, 2);
+        let weapon = |name| rules.find(name).unwrap();
+        let key = Key {
+            opponent: vec![('R', weapon("Rock")), ('K', weapon("Spock"))],
+            response: vec![
+                ('L', Response::Shape(weapon("Lizard"))),
+                ('W', Response::Outcome(Outcome::Win)),
+            ],
+        };
+        let games = parse_with("R L\nK W\nK L", &rules, &key).unwrap();
+        let scores = games
+            .iter()
+            .map(|game| game.score_game(&rules))
+            .collect::<Vec<_>>();
+        // Rock crushes Lizard, Paper disproves Spock, Lizard poisons Spock
+        assert_eq!(scores, [5, 2 + 2, 5 + 2]);
+
+        let error = parse_with("R X", &rules, &key).unwrap_err();
+        assert_eq!(error.reason, "Expected `L` or `W`");
+    }
+
+    #[test]
+    fn rejects_weapons_outside_the_rules() {
+        let key = Key {
+            opponent: vec![('A', Guess::ROCK), ('B', Guess(3))],
+            response: vec![('X', Response::Shape(Guess(7)))],
+        };
+        let error = parse_with("A X", &Rules::standard(), &key).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+        assert_eq!(error.reason, "No such weapon, the game has 3");
+        let error = parse_with("B X", &Rules::standard(), &key).unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (1, "B"));
     }
 }
@@ -0,0 +1,195 @@
+use std::fmt::{self, Display};
+
+use super::rules::Rules;
+use super::{parse, Game, Guess, Interpretation, Outcome, Response};
+use crate::ParseError;
+
+/// Every ordering of three items, as positions.
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// What the letters of the second column are assumed to stand for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Each letter is a different shape.
+    Shape,
+    /// Each letter is a different outcome.
+    Outcome,
+}
+
+/// The total score of the guide under one reading of its letters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    pub interpretation: Interpretation,
+    pub total: usize,
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = Rules::standard();
+        for (letter, response) in ['X', 'Y', 'Z'].iter().zip(self.interpretation.responses()) {
+            match response {
+                Response::Shape(shape) => write!(f, "{letter}={:<9}", rules.name(shape))?,
+                Response::Outcome(outcome) => write!(f, "{letter}={outcome:<9}")?,
+            }
+        }
+        write!(f, "{:>8}", self.total)
+    }
+}
+
+/// The score of every reading of a guide's second column in one mode, and
+/// the readings scoring best and worst.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Optimisation {
+    /// Every reading, in a fixed order
+    pub evaluations: Vec<Evaluation>,
+    pub max: usize,
+    pub min: usize,
+    /// The readings scoring `max`
+    pub best: Vec<Interpretation>,
+    /// The readings scoring `min`
+    pub worst: Vec<Interpretation>,
+}
+
+impl Optimisation {
+    /// Scores the guide in `input` under every bijection from `X`, `Y` and
+    /// `Z` to the shapes or outcomes, as chosen by `mode`.
+    pub fn new(input: &str, mode: Mode) -> Result<Self, ParseError> {
+        let rules = Rules::standard();
+        let tally = tally(input)?;
+        let evaluations = PERMUTATIONS
+            .iter()
+            .map(|permutation| {
+                let responses = permutation.map(|i| match mode {
+                    Mode::Shape => Response::Shape(Guess(i)),
+                    Mode::Outcome => {
+                        Response::Outcome([Outcome::Lose, Outcome::Draw, Outcome::Win][i])
+                    }
+                });
+                let interpretation = Interpretation::Custom(responses);
+                Evaluation {
+                    interpretation,
+                    total: score(&tally, &responses, &rules),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let max = evaluations.iter().map(|e| e.total).max().unwrap();
+        let min = evaluations.iter().map(|e| e.total).min().unwrap();
+        let matching = |total| {
+            evaluations
+                .iter()
+                .filter(|e| e.total == total)
+                .map(|e| e.interpretation)
+                .collect()
+        };
+        Ok(Self {
+            best: matching(max),
+            worst: matching(min),
+            evaluations,
+            max,
+            min,
+        })
+    }
+}
+
+/// Counts the rounds for each opponent shape and second-column letter.
+///
+/// Under the shape reading, the letters `X`, `Y` and `Z` are the shapes at
+/// positions 0, 1 and 2, so each game's choice gives its letter.
+fn tally(input: &str) -> Result<[[usize; 3]; 3], ParseError> {
+    let mut tally = [[0; 3]; 3];
+    for game in parse(input, Interpretation::Shape)? {
+        tally[game.opponent_choice.0][game.my_choice.0] += 1;
+    }
+    Ok(tally)
+}
+
+/// The total score of the tallied rounds with each letter read as `responses`.
+fn score(tally: &[[usize; 3]; 3], responses: &[Response; 3], rules: &Rules) -> usize {
+    let mut total = 0;
+    for (opponent, counts) in tally.iter().enumerate() {
+        for (letter, count) in counts.iter().enumerate() {
+            let opponent_choice = Guess(opponent);
+            // Every outcome is reachable against every standard shape
+            let my_choice = responses[letter].choose(opponent_choice, rules).unwrap();
+            let game = Game {
+                my_choice,
+                opponent_choice,
+            };
+            total += count * game.score_game(rules);
+        }
+    }
+    total
+}
+
+impl Display for Optimisation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Max: {}", self.max)?;
+        write!(f, "Min: {}", self.min)?;
+        for evaluation in &self.evaluations {
+            let marker = match evaluation.total {
+                total if total == self.max => "  best",
+                total if total == self.min => "  worst",
+                _ => "",
+            };
+            write!(f, "\n{evaluation}{marker}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y\nB X\nC Z";
+
+    #[test]
+    fn scores_every_shape_mapping() {
+        let optimisation = Optimisation::new(EXAMPLE, Mode::Shape).unwrap();
+        assert_eq!(optimisation.evaluations.len(), 6);
+        // The puzzle's reading is the identity mapping
+        assert_eq!(optimisation.evaluations[0].total, 15);
+        // X=Scissors Y=Paper Z=Rock wins every round
+        assert_eq!(optimisation.max, 6 * 3 + 2 + 1 + 3);
+        assert_eq!(
+            optimisation.best,
+            [Interpretation::Custom(
+                [Guess::SCISSORS, Guess::PAPER, Guess::ROCK].map(Response::Shape)
+            )]
+        );
+        assert_eq!(optimisation.min, 6);
+    }
+
+    #[test]
+    fn scores_every_outcome_mapping() {
+        let optimisation = Optimisation::new(EXAMPLE, Mode::Outcome).unwrap();
+        let totals = optimisation
+            .evaluations
+            .iter()
+            .map(|e| e.total)
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [12, 15, 15, 15, 18, 15]);
+        // The puzzle's reading happens to be the worst
+        assert_eq!(
+            optimisation.worst,
+            [Interpretation::Custom(Interpretation::Outcome.responses())]
+        );
+        assert_eq!(
+            optimisation.best,
+            [Interpretation::Custom(
+                [Outcome::Win, Outcome::Lose, Outcome::Draw].map(Response::Outcome)
+            )]
+        );
+        assert!(optimisation
+            .to_string()
+            .contains("X=Win      Y=Lose     Z=Draw           18  best"));
+    }
+}
@@ -0,0 +1,209 @@
+use super::{Guess, Outcome};
+
+/// A game in the style of Rock Paper Scissors: a set of weapons, which
+/// weapon beats which, and how many points each weapon and outcome is worth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    names: Vec<String>,
+    /// `beats[a][b]` is whether weapon `a` beats weapon `b`
+    beats: Vec<Vec<bool>>,
+    scores: Vec<usize>,
+    /// Points for losing, drawing and winning a round
+    outcome_points: [usize; 3],
+}
+
+impl Rules {
+    /// A game with the named weapons, where each `(winner, loser)` pair
+    /// gives one weapon that beats another, and `scores` gives the points
+    /// for playing each weapon.
+    ///
+    /// Every pair of distinct weapons must be decided exactly once, so that
+    /// no round between different weapons is a draw.
+    pub fn new(names: &[&str], beats: &[(&str, &str)], scores: &[usize]) -> Result<Self, String> {
+        if names.is_empty() {
+            return Err("A game needs at least one weapon".into());
+        }
+        if scores.len() != names.len() {
+            return Err(format!(
+                "Expected {} scores, one per weapon, found {}",
+                names.len(),
+                scores.len()
+            ));
+        }
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(format!("Weapon `{name}` is listed twice"));
+            }
+        }
+
+        let find = |name: &str| {
+            names
+                .iter()
+                .position(|weapon| *weapon == name)
+                .ok_or_else(|| format!("Unknown weapon `{name}`"))
+        };
+        let mut table = vec![vec![false; names.len()]; names.len()];
+        for &(winner, loser) in beats {
+            let (a, b) = (find(winner)?, find(loser)?);
+            if a == b {
+                return Err(format!("Weapon `{winner}` cannot beat itself"));
+            }
+            if table[b][a] {
+                return Err(format!("`{winner}` and `{loser}` both beat each other"));
+            }
+            table[a][b] = true;
+        }
+        for a in 0..names.len() {
+            for b in a + 1..names.len() {
+                if !table[a][b] && !table[b][a] {
+                    return Err(format!(
+                        "Neither `{}` nor `{}` beats the other",
+                        names[a], names[b]
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            beats: table,
+            scores: scores.to_vec(),
+            outcome_points: [0, 3, 6],
+        })
+    }
+
+    /// Rock, Paper and Scissors, worth 1, 2 and 3 points.
+    pub fn standard() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors"]).unwrap()
+    }
+
+    /// A balanced game where each weapon beats the one listed before it,
+    /// and every other weapon an odd distance back, wrapping around.
+    /// Weapons are worth 1, 2, 3 and so on in the order given.
+    ///
+    /// Rock, Paper, Scissors, Spock, Lizard gives
+    /// Rock-Paper-Scissors-Lizard-Spock.
+    pub fn cyclic(names: &[&str]) -> Result<Self, String> {
+        let n = names.len();
+        if n.is_multiple_of(2) {
+            return Err(format!(
+                "A balanced game needs an odd number of weapons, found {n}"
+            ));
+        }
+        let beats = (0..n)
+            .flat_map(|a| {
+                (1..n)
+                    .step_by(2)
+                    .map(move |distance| (a, (a + n - distance) % n))
+            })
+            .map(|(a, b)| (names[a], names[b]))
+            .collect::<Vec<_>>();
+        Self::new(names, &beats, &(1..=n).collect::<Vec<_>>())
+    }
+
+    /// The same game with different points for losing, drawing and winning.
+    pub fn with_outcome_points(mut self, lose: usize, draw: usize, win: usize) -> Self {
+        self.outcome_points = [lose, draw, win];
+        self
+    }
+
+    /// Number of weapons in the game.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Every weapon, in the order they were listed.
+    pub fn weapons(&self) -> impl Iterator<Item = Guess> {
+        (0..self.len()).map(Guess)
+    }
+
+    pub fn name(&self, weapon: Guess) -> &str {
+        &self.names[weapon.0]
+    }
+
+    /// The weapon with the given name.
+    pub fn find(&self, name: &str) -> Option<Guess> {
+        self.names.iter().position(|n| n == name).map(Guess)
+    }
+
+    /// Points for playing `weapon`.
+    pub fn score(&self, weapon: Guess) -> usize {
+        self.scores[weapon.0]
+    }
+
+    /// Points for a round with the given outcome.
+    pub fn outcome_points(&self, outcome: Outcome) -> usize {
+        self.outcome_points[outcome as usize]
+    }
+
+    pub fn beats(&self, a: Guess, b: Guess) -> bool {
+        self.beats[a.0][b.0]
+    }
+
+    /// The outcome of playing `mine` against `theirs`.
+    pub fn outcome(&self, mine: Guess, theirs: Guess) -> Outcome {
+        if self.beats(mine, theirs) {
+            Outcome::Win
+        } else if self.beats(theirs, mine) {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The first weapon, in the order listed, that gives `outcome` against
+    /// `opponent`, if any does.
+    pub fn respond(&self, opponent: Guess, outcome: Outcome) -> Option<Guess> {
+        self.weapons()
+            .find(|&weapon| self.outcome(weapon, opponent) == outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_balanced_games() {
+        let rules = Rules::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"]).unwrap();
+        let weapon = |name| rules.find(name).unwrap();
+        assert!(rules.beats(weapon("Rock"), weapon("Lizard")));
+        assert!(rules.beats(weapon("Lizard"), weapon("Spock")));
+        assert!(rules.beats(weapon("Spock"), weapon("Scissors")));
+        assert!(rules.beats(weapon("Paper"), weapon("Spock")));
+        assert_eq!(
+            rules.respond(weapon("Spock"), Outcome::Win),
+            Some(weapon("Paper"))
+        );
+        assert_eq!(rules.score(weapon("Lizard")), 5);
+        assert!(Rules::cyclic(&["Rock", "Paper"]).is_err());
+    }
+
+    #[test]
+    fn rejects_games_that_are_not_tournaments() {
+        let names = ["Rock", "Paper", "Scissors"];
+        let error = Rules::new(
+            &names,
+            &[("Paper", "Rock"), ("Rock", "Scissors")],
+            &[1, 2, 3],
+        );
+        assert_eq!(
+            error.unwrap_err(),
+            "Neither `Paper` nor `Scissors` beats the other"
+        );
+        let error = Rules::new(
+            &names[..2],
+            &[("Paper", "Rock"), ("Rock", "Paper")],
+            &[1, 2],
+        );
+        assert_eq!(
+            error.unwrap_err(),
+            "`Rock` and `Paper` both beat each other"
+        );
+        assert!(Rules::new(&names[..2], &[("Paper", "Stone")], &[1, 2]).is_err());
+    }
+}
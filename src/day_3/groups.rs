@@ -0,0 +1,175 @@
+use super::Compartment;
+
+/// Ways of splitting rucksacks into groups that each share exactly one
+/// item type, as found by [`discover`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Discovery {
+    /// Each partition lists its groups in order of their first rucksack,
+    /// and each group lists rucksacks by their position in the input.
+    pub partitions: Vec<Vec<Vec<usize>>>,
+    /// Whether the search ran to the end rather than stopping at its limit,
+    /// so every partition was found. If so and there are none, no valid
+    /// partition exists.
+    pub complete: bool,
+    /// Number of rucksacks tried as group members during the search.
+    pub explored: usize,
+}
+
+/// Splits `rucksacks`, in any order, into groups of `size` whose members
+/// share exactly one item type, stopping after `limit` partitions.
+///
+/// The search builds each group around the first rucksack not yet placed,
+/// adding later rucksacks only while the group still has items in common.
+///
+/// Fails if `size` is 0 or does not divide the number of rucksacks.
+pub fn discover(rucksacks: &[Compartment], size: usize, limit: usize) -> Result<Discovery, String> {
+    if size == 0 {
+        return Err("Groups must hold at least one rucksack".to_string());
+    }
+    if !rucksacks.len().is_multiple_of(size) {
+        return Err(format!(
+            "{} rucksacks cannot be split into groups of {size}",
+            rucksacks.len()
+        ));
+    }
+    let mut search = Search {
+        rucksacks,
+        size,
+        limit,
+        placed: vec![false; rucksacks.len()],
+        groups: vec![],
+        discovery: Discovery::default(),
+    };
+    search.next_group();
+    search.discovery.complete = search.discovery.partitions.len() < limit;
+    Ok(search.discovery)
+}
+
+struct Search<'a> {
+    rucksacks: &'a [Compartment],
+    size: usize,
+    limit: usize,
+    placed: Vec<bool>,
+    groups: Vec<Vec<usize>>,
+    discovery: Discovery,
+}
+
+impl Search<'_> {
+    fn next_group(&mut self) {
+        let Some(first) = self.placed.iter().position(|placed| !placed) else {
+            self.discovery.partitions.push(self.groups.clone());
+            return;
+        };
+        self.placed[first] = true;
+        self.extend(&mut vec![first], &self.rucksacks[first]);
+        self.placed[first] = false;
+    }
+
+    /// Completes `group`, whose members have `common` items in common,
+    /// with rucksacks after its last member.
+    fn extend(&mut self, group: &mut Vec<usize>, common: &Compartment) {
+        if self.discovery.partitions.len() >= self.limit {
+            return;
+        }
+        if group.len() == self.size {
+            if common.len() == 1 {
+                self.groups.push(group.clone());
+                self.next_group();
+                self.groups.pop();
+            }
+            return;
+        }
+        let start = group.last().map_or(0, |last| last + 1);
+        for next in start..self.rucksacks.len() {
+            let shared = common & &self.rucksacks[next];
+            if self.placed[next] || shared.is_empty() {
+                continue;
+            }
+            self.discovery.explored += 1;
+            self.placed[next] = true;
+            group.push(next);
+            self.extend(group, &shared);
+            group.pop();
+            self.placed[next] = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_3::priority::Alphabet;
+
+    fn compartments(lines: &[&str]) -> Vec<Compartment> {
+        let alphabet = Alphabet::default();
+        lines
+            .iter()
+            .map(|line| Compartment::from_contents(line, &alphabet).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn regroups_shuffled_rucksacks() {
+        // The example's groups, with badges `r` and `Z`, interleaved
+        let rucksacks = compartments(&[
+            "vJrwpWtwJgWrhcsFMMfFFhFp",
+            "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+            "ttgJtRGJQctTZtZT",
+            "PmmdzqPrVvPwwTWBwg",
+            "CrZsJsPPZsGzwwsLwLmpwMDw",
+        ]);
+        let discovery = discover(&rucksacks, 3, usize::MAX).unwrap();
+        assert!(discovery.complete);
+        // Besides the original groups, the first, fourth and sixth Elves
+        // share only `J` and the rest only `q`
+        assert_eq!(
+            discovery.partitions,
+            [
+                vec![vec![0, 2, 4], vec![1, 3, 5]],
+                vec![vec![0, 3, 5], vec![1, 2, 4]]
+            ]
+        );
+        for partition in &discovery.partitions {
+            for group in partition {
+                let common = Compartment::common(
+                    &group
+                        .iter()
+                        .map(|&i| rucksacks[i].clone())
+                        .collect::<Vec<_>>(),
+                );
+                assert_eq!(common.len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn proves_no_partition_exists() {
+        let discovery = discover(&compartments(&["ab", "bc", "cd"]), 3, usize::MAX).unwrap();
+        assert!(discovery.complete && discovery.partitions.is_empty());
+    }
+
+    #[test]
+    fn rejects_impossible_group_sizes() {
+        let rucksacks = compartments(&["ab", "ab"]);
+        assert_eq!(
+            discover(&rucksacks, 3, usize::MAX),
+            Err("2 rucksacks cannot be split into groups of 3".to_string())
+        );
+        assert!(discover(&rucksacks, 0, usize::MAX).is_err());
+        assert!(discover(&[], 0, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn lists_every_partition_up_to_the_limit() {
+        let rucksacks = compartments(&["ab", "ac", "bd", "cd"]);
+        let discovery = discover(&rucksacks, 2, usize::MAX).unwrap();
+        assert_eq!(
+            discovery.partitions,
+            [vec![vec![0, 1], vec![2, 3]], vec![vec![0, 2], vec![1, 3]]]
+        );
+        let discovery = discover(&rucksacks, 2, 1).unwrap();
+        assert_eq!(discovery.partitions.len(), 1);
+        assert!(!discovery.complete);
+    }
+}
@@ -1,106 +1,500 @@
-use std::collections::{HashMap, HashSet};
+pub mod groups;
+pub mod priority;
+pub mod validate;
 
-fn score(ch: char) -> u32 {
-    if ch.is_lowercase() {
-        ch as u32 - 96
-    } else if ch.is_uppercase() {
-        ch as u32 - 38
-    } else {
-        unreachable!("All items are represented by uppercase or lowercase letters")
-    }
+use std::hash::{Hash, Hasher};
+use std::ops::{BitAnd, BitOr, Sub};
+
+use crate::{ParseError, Solution};
+use priority::{Alphabet, PriorityScheme};
+use validate::{validate, GROUP_SIZE};
+
+/// Number of item types held by each word of a [`Compartment`].
+const WORD_BITS: u32 = u64::BITS;
+
+/// A set of item types, stored as a bitmask with bit `n` set when item `n`
+/// of an [`Alphabet`] is present.
+///
+/// Sets over alphabets of up to 64 items fit in a single inline `u64`.
+/// Larger alphabets spill onto the heap, growing a word at a time.
+#[derive(Clone, Debug, Default)]
+pub struct Compartment {
+    words: Words,
 }
 
-#[derive(Debug)]
-struct Compartment(HashSet<char>);
+/// The words of a [`Compartment`], kept inline while one is enough.
+#[derive(Clone, Debug)]
+enum Words {
+    Inline(u64),
+    Spilled(Vec<u64>),
+}
+
+impl Default for Words {
+    fn default() -> Self {
+        Words::Inline(0)
+    }
+}
 
 impl Compartment {
-    fn from_contents(contents: &str) -> Self {
-        let mut set = HashSet::new();
-        for ch in contents.chars() {
-            set.insert(ch);
+    pub const EMPTY: Self = Self {
+        words: Words::Inline(0),
+    };
+
+    /// An empty set with room for every item of `alphabet`.
+    pub fn empty(alphabet: &Alphabet) -> Self {
+        let words = alphabet.len().div_ceil(WORD_BITS as usize);
+        Self {
+            words: match words {
+                0 | 1 => Words::Inline(0),
+                words => Words::Spilled(vec![0; words]),
+            },
         }
-        Self(set)
     }
 
-    fn score_conflict(&self, other: &Self) -> Option<u32> {
-        self.find_conflict(other).into_iter().last().map(score)
+    /// The set of items in `contents`, or `None` if any of them is not in
+    /// `alphabet`.
+    pub fn from_contents(contents: &str, alphabet: &Alphabet) -> Option<Self> {
+        contents.chars().try_fold(Self::empty(alphabet), |set, ch| {
+            Some(set.with(alphabet.index(ch)?))
+        })
     }
 
-    fn find_conflict(&self, other: &Self) -> Vec<char> {
-        other
-            .0
-            .iter()
-            .filter(|ch| self.0.contains(ch))
-            .copied()
-            .collect::<Vec<_>>()
+    /// The set with item `index` added.
+    pub fn with(mut self, index: u32) -> Self {
+        let word = (index / WORD_BITS) as usize;
+        let bit = 1 << (index % WORD_BITS);
+        match &mut self.words {
+            Words::Inline(bits) if word == 0 => *bits |= bit,
+            Words::Inline(bits) => {
+                let mut words = vec![0; word + 1];
+                words[0] = *bits;
+                words[word] = bit;
+                self.words = Words::Spilled(words);
+            }
+            Words::Spilled(words) => {
+                if word >= words.len() {
+                    words.resize(word + 1, 0);
+                }
+                words[word] |= bit;
+            }
+        }
+        self
     }
 
-    fn find_badge(&self, others: &[Self]) -> Option<char> {
-        let conflicts = others
+    pub fn contains(&self, index: u32) -> bool {
+        self.word((index / WORD_BITS) as usize) >> (index % WORD_BITS) & 1 == 1
+    }
+
+    /// Number of distinct item types.
+    pub fn len(&self) -> usize {
+        self.words()
             .iter()
-            .flat_map(|other| self.find_conflict(other))
-            .collect::<Vec<_>>();
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
 
-        let mut occurrences = HashMap::new();
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(|&word| word == 0)
+    }
 
-        for ch in conflicts {
-            *occurrences.entry(ch).or_insert(1) += 1;
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// The items in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    fn words(&self) -> &[u64] {
+        match &self.words {
+            Words::Inline(bits) => std::slice::from_ref(bits),
+            Words::Spilled(words) => words,
         }
+    }
+
+    /// Word `index` of the bitmask, where words past the end are empty.
+    fn word(&self, index: usize) -> u64 {
+        self.words().get(index).copied().unwrap_or(0)
+    }
+
+    /// The words without trailing empty ones, so equal sets compare equal
+    /// whatever room they were given.
+    fn trimmed(&self) -> &[u64] {
+        let words = self.words();
+        let end = words.iter().rposition(|&word| word != 0);
+        &words[..end.map_or(0, |end| end + 1)]
+    }
+
+    fn combine(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let words = match (&self.words, &other.words) {
+            (Words::Inline(a), Words::Inline(b)) => Words::Inline(op(*a, *b)),
+            _ => {
+                let len = self.words().len().max(other.words().len());
+                Words::Spilled(
+                    (0..len)
+                        .map(|index| op(self.word(index), other.word(index)))
+                        .collect(),
+                )
+            }
+        };
+        Self { words }
+    }
+
+    /// The index of each item, in ascending order.
+    pub fn indices(&self) -> impl Iterator<Item = u32> + '_ {
+        self.words().iter().enumerate().flat_map(|(word, &bits)| {
+            let mut bits = bits;
+            std::iter::from_fn(move || {
+                let index = bits.trailing_zeros();
+                // Clear the lowest set bit
+                bits &= bits.checked_sub(1)?;
+                Some(word as u32 * WORD_BITS + index)
+            })
+        })
+    }
+
+    /// Each item, in ascending order of priority.
+    pub fn items<'a>(&'a self, alphabet: &'a Alphabet) -> impl Iterator<Item = char> + 'a {
+        self.indices().map(|index| alphabet.item(index))
+    }
+
+    /// The index of the only item, or `None` if there are none or several.
+    pub fn only(&self) -> Option<u32> {
+        let mut indices = self.indices();
+        indices.next().filter(|_| indices.next().is_none())
+    }
+
+    /// The priority of the item in both compartments.
+    ///
+    /// `None` when there is no such item, and also when there are several:
+    /// picking one of them would give an answer the puzzle does not define.
+    fn score_conflict(&self, other: &Self, alphabet: &Alphabet) -> Option<u32> {
+        self.find_conflict(other)
+            .only()
+            .map(|index| alphabet.priority(index))
+    }
 
-        if occurrences.is_empty() {
-            return None;
+    fn find_conflict(&self, other: &Self) -> Self {
+        self.intersection(other)
+    }
+
+    /// Every item type carried by all of `rucksacks`.
+    pub fn common(rucksacks: &[Self]) -> Self {
+        match rucksacks.split_first() {
+            Some((first, rest)) => first.find_badges(rest),
+            None => Self::EMPTY,
         }
+    }
+
+    /// Every item type this rucksack shares with all of `others`.
+    pub fn find_badges(&self, others: &[Self]) -> Self {
+        others
+            .iter()
+            .fold(self.clone(), |common, other| &common & other)
+    }
+}
 
-        Some(
-            occurrences
-                .into_iter()
-                .max_by_key(|&(_, count)| count)
-                .map(|(val, _)| val)
-                .expect("Cannot compute the mode of zero numbers"),
-        )
+impl PartialEq for Compartment {
+    fn eq(&self, other: &Self) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl Eq for Compartment {}
+
+impl Hash for Compartment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.trimmed().hash(state);
+    }
+}
+
+impl BitOr for &Compartment {
+    type Output = Compartment;
+
+    fn bitor(self, other: Self) -> Compartment {
+        self.union(other)
+    }
+}
+
+impl BitAnd for &Compartment {
+    type Output = Compartment;
+
+    fn bitand(self, other: Self) -> Compartment {
+        self.intersection(other)
+    }
+}
+
+impl Sub for &Compartment {
+    type Output = Compartment;
+
+    fn sub(self, other: Self) -> Compartment {
+        self.difference(other)
+    }
+}
+
+impl FromIterator<u32> for Compartment {
+    fn from_iter<I: IntoIterator<Item = u32>>(indices: I) -> Self {
+        indices.into_iter().fold(Self::EMPTY, Self::with)
+    }
+}
+
+/// The rucksacks of a puzzle input, with the alphabet their items belong to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inventory {
+    pub alphabet: Alphabet,
+    pub rucksacks: Vec<String>,
+}
+
+impl Inventory {
+    /// Reads one rucksack per line, with items drawn from `scheme`.
+    ///
+    /// Fails on any [fatal](validate::Diagnostic::is_fatal) problem with a single
+    /// rucksack found by [`validate()`], so every rucksack can be scored.
+    /// Problems with a [group](validate::Diagnostic::group) only leave part two
+    /// without an answer.
+    pub fn parse(input: &str, scheme: &PriorityScheme) -> Result<Self, ParseError> {
+        let input = input.trim_end();
+        let alphabet = Alphabet::new(scheme, input)?;
+        let rucksacks = input.lines().map(str::to_string).collect::<Vec<_>>();
+        if let Some(diagnostic) = validate(input, scheme)
+            .into_iter()
+            .find(|diagnostic| diagnostic.is_fatal() && diagnostic.group().is_none())
+        {
+            let index = diagnostic.line() - 1;
+            let line = rucksacks.get(index).map_or("", String::as_str);
+            return Err(ParseError::new(Day3::DAY, diagnostic.reason()).at(index, line, line));
+        }
+        Ok(Self {
+            alphabet,
+            rucksacks,
+        })
+    }
+
+    /// The set of items in each rucksack.
+    pub fn compartments(&self) -> impl Iterator<Item = Compartment> + '_ {
+        self.rucksacks.iter().map(|rucksack| {
+            Compartment::from_contents(rucksack, &self.alphabet)
+                .expect("Items are checked against the alphabet when parsing")
+        })
+    }
+}
+
+/// The sum of each rucksack's conflict priority, or `None` if any rucksack
+/// has several items in both compartments.
+fn sum_of_conflicts(inventory: &Inventory) -> Option<u32> {
+    let alphabet = &inventory.alphabet;
+    inventory
+        .rucksacks
+        .iter()
+        .map(|rucksack| {
+            // Items may take more than one byte outside the AoC scheme
+            let middle = rucksack
+                .char_indices()
+                .nth(rucksack.chars().count() / 2)
+                .map_or(rucksack.len(), |(position, _)| position);
+            let compartment = |contents| {
+                Compartment::from_contents(contents, alphabet)
+                    .expect("Items are checked against the alphabet when parsing")
+            };
+            let compartment_1 = compartment(&rucksack[..middle]);
+            let compartment_2 = compartment(&rucksack[middle..]);
+            compartment_1.score_conflict(&compartment_2, alphabet)
+        })
+        .sum()
+}
+
+/// The sum of each group's badge priority, or `None` if the rucksacks do not
+/// split into groups of three that each carry exactly one badge.
+fn sum_of_badges(inventory: &Inventory) -> Option<u32> {
+    if !inventory.rucksacks.len().is_multiple_of(GROUP_SIZE) {
+        return None;
+    }
+    inventory
+        .compartments()
+        .array_chunks()
+        .map(|[compartment_1, compartment_2, compartment_3]| {
+            compartment_1
+                .find_badges(&[compartment_2, compartment_3])
+                .only()
+                .map(|index| inventory.alphabet.priority(index))
+        })
+        .sum()
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Inventory;
+    type PartOne = Option<u32>;
+    type PartTwo = Option<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Inventory::parse(input, &PriorityScheme::Aoc)
+    }
+
+    fn part_one(inventory: &Self::Input) -> Self::PartOne {
+        sum_of_conflicts(inventory)
+    }
+
+    fn part_two(inventory: &Self::Input) -> Self::PartTwo {
+        sum_of_badges(inventory)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::{score, Compartment};
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::{Answer, Part};
 
     #[test]
     fn day_3_challenge_1() {
-        let input = include_str!("input").trim();
-        let result = input
-            .split('\n')
-            .map(|rucksack| {
-                let number_of_items = rucksack.len() / 2;
-                let contents = [&rucksack[..number_of_items], &rucksack[number_of_items..]];
-                let compartment_1 = Compartment::from_contents(contents[0]);
-                let compartment_2 = Compartment::from_contents(contents[1]);
-                let conflict_score = compartment_1.score_conflict(&compartment_2);
-                assert!(conflict_score.is_some());
-                conflict_score.unwrap()
-            })
-            .sum::<u32>();
-        assert_eq!(result, 7848);
+        let input = include_str!("input");
+        let inventory = Day3::parse(input).unwrap();
+        let result = sum_of_conflicts(&inventory);
+        assert_answer(3, Part::One, "input", result);
     }
 
     #[test]
     fn day_3_challenge_2() {
-        let input = include_str!("input").trim();
-        let result = input
-            .split('\n')
-            .array_chunks()
-            .map(|[rucksack_1, rucksack_2, rucksack_3]| {
-                let rucksacks = [rucksack_1, rucksack_2, rucksack_3];
-                let compartments = rucksacks
-                    .iter()
-                    .map(|contents| Compartment::from_contents(contents))
-                    .collect::<Vec<_>>();
-                let score = compartments[0].find_badge(&compartments[1..]).map(score);
-                assert!(score.is_some());
-                score.unwrap()
-            })
-            .sum::<u32>();
-        assert_eq!(result, 2616);
+        let input = include_str!("input");
+        let inventory = Day3::parse(input).unwrap();
+        let result = sum_of_badges(&inventory);
+        assert_answer(3, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_invalid_item() {
+        let error = Day3::parse("vJrwpWtwJgWr\nabc-ef").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn rejects_unscorable_rucksacks() {
+        let error = Day3::parse("abcab\naa\nbb").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (1, "abcab"));
+        let error = Day3::parse("abcd\nab").unwrap_err();
+        assert_eq!(
+            (error.line, error.reason.as_str()),
+            (1, "No item in both compartments")
+        );
+        assert!(Day3::parse("abab\nbcbc\nbdbd\nef").is_err());
+    }
+
+    #[test]
+    fn leaves_part_two_unanswered_for_bad_groups() {
+        let inventory = Day3::parse("aa\nbb\ncc").unwrap();
+        assert_eq!(Day3::part_one(&inventory), Some(1 + 2 + 3));
+        assert_eq!(Day3::part_two(&inventory), None);
+        let inventory = Day3::parse("abca\nbdeb\nbfgb\nhh").unwrap();
+        assert_eq!(Day3::part_one(&inventory), Some(1 + 2 + 2 + 8));
+        assert_eq!(Day3::part_two(&inventory), None);
+        assert_eq!(Answer::from(Day3::part_two(&inventory)).to_string(), "none");
+    }
+
+    #[test]
+    fn leaves_ambiguous_parts_unanswered() {
+        // Each rucksack has one conflict, but the group shares `a` to `d`
+        let inventory = Day3::parse("abxcdx\nabycdy\nabzcdz").unwrap();
+        assert_eq!(Day3::part_one(&inventory), Some(24 + 25 + 26));
+        assert_eq!(Day3::part_two(&inventory), None);
+        // The first rucksack has both `a` and `b` in each compartment
+        let inventory = Day3::parse("abab\nbcbc\nbdbd").unwrap();
+        assert_eq!(Day3::part_one(&inventory), None);
+        assert_eq!(Day3::part_two(&inventory), Some(2));
+        let set = [3, 70].into_iter().collect::<Compartment>();
+        assert_eq!(set.only(), None);
+        assert_eq!(Compartment::EMPTY.with(70).only(), Some(70));
+        assert_eq!(Compartment::EMPTY.only(), None);
+    }
+
+    #[test]
+    fn combines_compartments() {
+        let alphabet = Alphabet::default();
+        let a = Compartment::from_contents("vJrwpWtwJgWr", &alphabet).unwrap();
+        let b = Compartment::from_contents("hcsFMMfFFhFp", &alphabet).unwrap();
+        assert_eq!((&a & &b).items(&alphabet).collect::<String>(), "p");
+        assert_eq!(a.len(), 8);
+        assert_eq!((&a | &b).len(), 8 + 7 - 1);
+        assert_eq!((&a - &b).items(&alphabet).collect::<String>(), "grtvwJW");
+        let index = |item| alphabet.index(item).unwrap();
+        assert!(a.contains(index('J')) && !a.contains(index('j')));
+        let set = [0, 51, 26].into_iter().collect::<Compartment>();
+        assert_eq!(set.items(&alphabet).collect::<String>(), "aAZ");
+    }
+
+    #[test]
+    fn finds_every_common_item() {
+        let alphabet = Alphabet::default();
+        let group = ["abcXY", "bcdXY", "cXYzb"]
+            .map(|items| Compartment::from_contents(items, &alphabet).unwrap());
+        let common = Compartment::common(&group);
+        assert_eq!(common.items(&alphabet).collect::<String>(), "bcXY");
+        assert_eq!(group[0].find_badges(&group[1..]), common);
+        assert!(Compartment::common(&[]).is_empty());
+        assert_eq!(Compartment::empty(&alphabet), Compartment::EMPTY);
+    }
+
+    #[test]
+    fn scores_items_outside_the_aoc_alphabet() {
+        let input = "aé9é\nx9y9\n9bcb";
+        assert!(Day3::parse(input).is_err());
+        let inventory = Inventory::parse(input, &PriorityScheme::Unicode).unwrap();
+        assert_eq!(
+            sum_of_conflicts(&inventory),
+            Some((0xe9 + 52) + ('9' as u32 + 52) + 2)
+        );
+        assert_eq!(sum_of_badges(&inventory), Some('9' as u32 + 52));
+    }
+
+    #[test]
+    fn holds_alphabets_wider_than_a_word() {
+        // 199 item types, so the shared item has index 99, in the second word
+        let items = ('\u{100}'..'\u{1c7}').collect::<Vec<_>>();
+        let line = |indices: &[usize]| indices.iter().map(|&i| items[i]).collect::<String>();
+        let input = [
+            line(&(0..100).chain(99..199).collect::<Vec<_>>()),
+            line(&[99, 150, 160, 99]),
+            line(&[99, 10, 10, 20]),
+        ]
+        .join("\n");
+        let inventory = Inventory::parse(&input, &PriorityScheme::Unicode).unwrap();
+        assert_eq!(inventory.alphabet.len(), 199);
+        let priority = |i: usize| items[i] as u32 + 52;
+        assert_eq!(
+            sum_of_conflicts(&inventory),
+            Some(2 * priority(99) + priority(10))
+        );
+        assert_eq!(sum_of_badges(&inventory), Some(priority(99)));
+        assert_eq!(Compartment::EMPTY.with(130), [130].into_iter().collect());
+        assert_ne!(Compartment::EMPTY.with(130), Compartment::EMPTY.with(2));
+        assert_eq!(
+            Compartment::EMPTY.with(70).indices().collect::<Vec<_>>(),
+            [70]
+        );
+        // A set spilled onto the heap still equals its inline counterpart
+        let wide = Compartment::EMPTY.with(2).with(130);
+        assert_eq!(
+            &wide - &Compartment::EMPTY.with(130),
+            Compartment::EMPTY.with(2)
+        );
+    }
+
+    #[test]
+    fn rejects_items_outside_the_alphabet() {
+        let alphabet = Alphabet::default();
+        assert!(Compartment::from_contents("ab-c", &alphabet).is_none());
+        assert_eq!(
+            Compartment::from_contents("", &alphabet),
+            Some(Compartment::EMPTY)
+        );
     }
 }
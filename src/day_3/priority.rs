@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Day3;
+use crate::{ParseError, Solution};
+
+/// How much each item type is worth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PriorityScheme {
+    /// `a` to `z` are worth 1 to 26, and `A` to `Z` 27 to 52.
+    Aoc,
+    /// Only the listed items, each worth the given priority.
+    Table(BTreeMap<char, u32>),
+    /// ASCII letters as in [`Aoc`](Self::Aoc). Letters and digits from any
+    /// other script are worth 52 more than their code point, so they rank
+    /// after every ASCII letter.
+    Unicode,
+}
+
+impl PriorityScheme {
+    /// The priority of `item`, if the scheme includes it.
+    pub fn priority(&self, item: char) -> Option<u32> {
+        match self {
+            PriorityScheme::Aoc | PriorityScheme::Unicode if item.is_ascii_lowercase() => {
+                Some(item as u32 - 'a' as u32 + 1)
+            }
+            PriorityScheme::Aoc | PriorityScheme::Unicode if item.is_ascii_uppercase() => {
+                Some(item as u32 - 'A' as u32 + 27)
+            }
+            PriorityScheme::Aoc => None,
+            PriorityScheme::Table(table) => table.get(&item).copied(),
+            PriorityScheme::Unicode if item.is_alphanumeric() => Some(item as u32 + 52),
+            PriorityScheme::Unicode => None,
+        }
+    }
+
+    /// Why an item outside the scheme was rejected.
+    fn reason(&self) -> &'static str {
+        match self {
+            PriorityScheme::Aoc => "Items must be lowercase or uppercase letters",
+            PriorityScheme::Table(_) => "Item is not in the priority table",
+            PriorityScheme::Unicode => "Items must be letters or digits",
+        }
+    }
+}
+
+/// The item types that can appear in an inventory, each numbered so it
+/// can be stored as one bit of a [`Compartment`](super::Compartment).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    /// Items in ascending order of priority, so bit `n` is `items[n]`
+    items: Vec<(char, u32)>,
+    indices: BTreeMap<char, u32>,
+}
+
+impl Alphabet {
+    /// The alphabet of `scheme`, checking every item in `input` belongs
+    /// to it.
+    ///
+    /// The items of the Unicode scheme are those found in `input`, as
+    /// there are too many to number them all.
+    pub fn new(scheme: &PriorityScheme, input: &str) -> Result<Self, ParseError> {
+        let mut items = match scheme {
+            PriorityScheme::Aoc => ('a'..='z').chain('A'..='Z').collect(),
+            PriorityScheme::Table(table) => table.keys().copied().collect(),
+            PriorityScheme::Unicode => BTreeSet::new(),
+        };
+
+        for (index, line) in input.lines().enumerate() {
+            for (position, item) in line.char_indices() {
+                if scheme.priority(item).is_none() {
+                    let token = &line[position..position + item.len_utf8()];
+                    return Err(ParseError::new(Day3::DAY, scheme.reason()).at(index, line, token));
+                }
+                if *scheme == PriorityScheme::Unicode {
+                    items.insert(item);
+                }
+            }
+        }
+        Ok(Self::from_items(scheme, items.into_iter().collect()))
+    }
+
+    fn from_items(scheme: &PriorityScheme, items: Vec<char>) -> Self {
+        let mut items = items
+            .into_iter()
+            .map(|item| (item, scheme.priority(item).unwrap()))
+            .collect::<Vec<_>>();
+        items.sort_by_key(|&(item, priority)| (priority, item));
+        let indices = items
+            .iter()
+            .enumerate()
+            .map(|(index, &(item, _))| (item, index as u32))
+            .collect();
+        Self { items, indices }
+    }
+
+    /// Number of item types.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The bit that stands for `item`, if it is in the alphabet.
+    pub fn index(&self, item: char) -> Option<u32> {
+        self.indices.get(&item).copied()
+    }
+
+    /// The item that bit `index` stands for.
+    pub fn item(&self, index: u32) -> char {
+        self.items[index as usize].0
+    }
+
+    /// The priority of the item that bit `index` stands for.
+    pub fn priority(&self, index: u32) -> u32 {
+        self.items[index as usize].1
+    }
+}
+
+impl Default for Alphabet {
+    /// The alphabet of the [`Aoc`](PriorityScheme::Aoc) scheme.
+    fn default() -> Self {
+        let scheme = PriorityScheme::Aoc;
+        Self::from_items(&scheme, ('a'..='z').chain('A'..='Z').collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_items_by_scheme() {
+        assert_eq!(PriorityScheme::Aoc.priority('p'), Some(16));
+        assert_eq!(PriorityScheme::Aoc.priority('L'), Some(38));
+        assert_eq!(PriorityScheme::Aoc.priority('é'), None);
+        assert_eq!(PriorityScheme::Unicode.priority('é'), Some(0xe9 + 52));
+        assert_eq!(PriorityScheme::Unicode.priority('7'), Some(55 + 52));
+        assert_eq!(PriorityScheme::Unicode.priority('-'), None);
+        let table = PriorityScheme::Table(BTreeMap::from([('x', 5), ('!', 1)]));
+        assert_eq!(table.priority('!'), Some(1));
+        assert_eq!(table.priority('a'), None);
+    }
+
+    #[test]
+    fn numbers_items_in_priority_order() {
+        let alphabet = Alphabet::default();
+        assert_eq!(alphabet.len(), 52);
+        assert_eq!(alphabet.index('a'), Some(0));
+        assert_eq!((alphabet.item(26), alphabet.priority(26)), ('A', 27));
+
+        let alphabet = Alphabet::new(&PriorityScheme::Unicode, "ßa\nZ9ß").unwrap();
+        let items = (0..alphabet.len() as u32)
+            .map(|index| alphabet.item(index))
+            .collect::<String>();
+        assert_eq!(items, "aZ9ß");
+    }
+
+    #[test]
+    fn rejects_items_outside_the_scheme() {
+        let error = Alphabet::new(&PriorityScheme::Aoc, "ab\ncdé").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "é"));
+        let many = ('\u{100}'..'\u{180}').collect::<String>();
+        let alphabet = Alphabet::new(&PriorityScheme::Unicode, &many).unwrap();
+        assert_eq!(alphabet.len(), 128);
+    }
+}
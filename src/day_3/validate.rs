@@ -0,0 +1,260 @@
+use std::fmt::{self, Display};
+
+use super::priority::{Alphabet, PriorityScheme};
+use super::Compartment;
+
+/// Number of Elves, and so rucksacks, in each group.
+pub const GROUP_SIZE: usize = 3;
+
+/// A problem with an inventory that would make its answers unreliable.
+///
+/// Lines and columns are 1-based, and groups are numbered from 1 in the
+/// order they appear.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// An item outside the priority scheme.
+    InvalidItem {
+        line: usize,
+        column: usize,
+        item: char,
+    },
+    /// A rucksack that cannot be split into two equal compartments.
+    OddLength { line: usize, length: usize },
+    /// A rucksack with no item in both compartments.
+    NoConflict { line: usize },
+    /// A rucksack with more than one item type in both compartments.
+    MultipleConflicts { line: usize, items: Vec<char> },
+    /// A group with no item carried by every Elf.
+    MissingBadge { group: usize },
+    /// A group with more than one item type carried by every Elf.
+    AmbiguousBadge { group: usize, items: Vec<char> },
+    /// A final group with fewer than [`GROUP_SIZE`] rucksacks.
+    IncompleteGroup { group: usize, rucksacks: usize },
+}
+
+impl Diagnostic {
+    /// The line the problem is on, or the first line of its group.
+    pub fn line(&self) -> usize {
+        match self {
+            Diagnostic::InvalidItem { line, .. }
+            | Diagnostic::OddLength { line, .. }
+            | Diagnostic::NoConflict { line }
+            | Diagnostic::MultipleConflicts { line, .. } => *line,
+            Diagnostic::MissingBadge { group }
+            | Diagnostic::AmbiguousBadge { group, .. }
+            | Diagnostic::IncompleteGroup { group, .. } => (group - 1) * GROUP_SIZE + 1,
+        }
+    }
+
+    /// The group the problem is in, if it concerns a whole group rather than
+    /// a single rucksack, and so only the badges of part two.
+    pub fn group(&self) -> Option<usize> {
+        match self {
+            Diagnostic::MissingBadge { group }
+            | Diagnostic::AmbiguousBadge { group, .. }
+            | Diagnostic::IncompleteGroup { group, .. } => Some(*group),
+            _ => None,
+        }
+    }
+
+    /// Whether the puzzle has no answer at all, rather than an answer that
+    /// depends on which of several items is chosen. The solver leaves the
+    /// affected part unanswered either way.
+    pub fn is_fatal(&self) -> bool {
+        !matches!(
+            self,
+            Diagnostic::MultipleConflicts { .. } | Diagnostic::AmbiguousBadge { .. }
+        )
+    }
+
+    /// What is wrong, without where.
+    pub fn reason(&self) -> String {
+        let list = |items: &[char]| {
+            items
+                .iter()
+                .map(|item| format!("`{item}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Diagnostic::InvalidItem { item, .. } => format!("Invalid item `{item}`"),
+            Diagnostic::OddLength { length, .. } => {
+                format!("Odd number of items ({length})")
+            }
+            Diagnostic::NoConflict { .. } => "No item in both compartments".to_string(),
+            Diagnostic::MultipleConflicts { items, .. } => {
+                format!("Several items in both compartments: {}", list(items))
+            }
+            Diagnostic::MissingBadge { .. } => "No badge".to_string(),
+            Diagnostic::AmbiguousBadge { items, .. } => {
+                format!("Several possible badges: {}", list(items))
+            }
+            Diagnostic::IncompleteGroup { rucksacks, .. } => {
+                format!("Only {rucksacks} of {GROUP_SIZE} rucksacks")
+            }
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::InvalidItem { line, column, .. } => {
+                write!(f, "line {line}, column {column}: ")?
+            }
+            Diagnostic::OddLength { line, .. }
+            | Diagnostic::NoConflict { line }
+            | Diagnostic::MultipleConflicts { line, .. } => write!(f, "line {line}: ")?,
+            Diagnostic::MissingBadge { group }
+            | Diagnostic::AmbiguousBadge { group, .. }
+            | Diagnostic::IncompleteGroup { group, .. } => write!(f, "group {group}: ")?,
+        }
+        write!(f, "{}", self.reason())
+    }
+}
+
+/// Checks every rucksack and group in `input` against the puzzle's
+/// assumptions, returning every problem found rather than stopping at the
+/// first.
+pub fn validate(input: &str, scheme: &PriorityScheme) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let input = input.trim_end();
+
+    // Drop invalid items, so the remaining checks see only the valid ones
+    let mut valid = String::new();
+    for (index, line) in input.lines().enumerate() {
+        for (column, item) in line.chars().enumerate() {
+            match scheme.priority(item) {
+                Some(_) => valid.push(item),
+                None => diagnostics.push(Diagnostic::InvalidItem {
+                    line: index + 1,
+                    column: column + 1,
+                    item,
+                }),
+            }
+        }
+        valid.push('\n');
+    }
+    let alphabet = Alphabet::new(scheme, &valid).expect("Invalid items were dropped");
+    let items = |set: Compartment| set.items(&alphabet).collect::<Vec<_>>();
+
+    let lines = input.lines().collect::<Vec<_>>();
+    // Invalid items are already reported, so leave them out of the sets
+    let compartment = |line: &str| {
+        line.chars()
+            .filter_map(|item| alphabet.index(item))
+            .collect::<Compartment>()
+    };
+    for (index, line) in lines.iter().enumerate() {
+        let line_number = index + 1;
+        let length = line.chars().count();
+        if length % 2 == 1 {
+            diagnostics.push(Diagnostic::OddLength {
+                line: line_number,
+                length,
+            });
+            continue;
+        }
+        let middle = line
+            .char_indices()
+            .nth(length / 2)
+            .map_or(line.len(), |(i, _)| i);
+        let first = compartment(&line[..middle]);
+        let second = compartment(&line[middle..]);
+        let conflicts = &first & &second;
+        match conflicts.len() {
+            0 => diagnostics.push(Diagnostic::NoConflict { line: line_number }),
+            1 => {}
+            _ => diagnostics.push(Diagnostic::MultipleConflicts {
+                line: line_number,
+                items: items(conflicts),
+            }),
+        }
+    }
+
+    for (index, group) in lines.chunks(GROUP_SIZE).enumerate() {
+        let group_number = index + 1;
+        if group.len() < GROUP_SIZE {
+            diagnostics.push(Diagnostic::IncompleteGroup {
+                group: group_number,
+                rucksacks: group.len(),
+            });
+            continue;
+        }
+        let compartments = group
+            .iter()
+            .map(|line| compartment(line))
+            .collect::<Vec<_>>();
+        let badges = Compartment::common(&compartments);
+        match badges.len() {
+            0 => diagnostics.push(Diagnostic::MissingBadge {
+                group: group_number,
+            }),
+            1 => {}
+            _ => diagnostics.push(Diagnostic::AmbiguousBadge {
+                group: group_number,
+                items: items(badges),
+            }),
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_the_example() {
+        let input =
+            "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\n\
+                     wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\nttgJtRGJQctTZtZT\nCrZsJsPPZsGzwwsLwLmpwMDw\n";
+        assert_eq!(validate(input, &PriorityScheme::Aoc), []);
+        assert_eq!(validate(include_str!("input"), &PriorityScheme::Aoc), []);
+    }
+
+    #[test]
+    fn reports_each_problem() {
+        let input = "abcab\naBbaBb\nab-ab\nxyzw";
+        let diagnostics = validate(input, &PriorityScheme::Aoc);
+        assert_eq!(
+            diagnostics,
+            [
+                Diagnostic::InvalidItem {
+                    line: 3,
+                    column: 3,
+                    item: '-'
+                },
+                Diagnostic::OddLength { line: 1, length: 5 },
+                Diagnostic::MultipleConflicts {
+                    line: 2,
+                    items: vec!['a', 'b', 'B']
+                },
+                Diagnostic::OddLength { line: 3, length: 5 },
+                Diagnostic::NoConflict { line: 4 },
+                Diagnostic::AmbiguousBadge {
+                    group: 1,
+                    items: vec!['a', 'b']
+                },
+                Diagnostic::IncompleteGroup {
+                    group: 2,
+                    rucksacks: 1
+                },
+            ]
+        );
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "line 2: Several items in both compartments: `a`, `b`, `B`"
+        );
+        assert_eq!(diagnostics[6].to_string(), "group 2: Only 1 of 3 rucksacks");
+        assert_eq!(diagnostics[6].line(), 4);
+        assert_eq!(diagnostics[6].group(), Some(2));
+        assert_eq!(diagnostics[4].group(), None);
+    }
+
+    #[test]
+    fn reports_missing_badges() {
+        let diagnostics = validate("aa\nbb\ncc", &PriorityScheme::Aoc);
+        assert_eq!(diagnostics, [Diagnostic::MissingBadge { group: 1 }]);
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::{self, Display};
+
+use super::Assignment;
+use crate::interval::{Interval, IntervalSet};
+
+/// How one Elf's sections relate to everyone else's.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElfCoverage {
+    /// The line of the input the Elf's pair is on, from 1
+    pub line: usize,
+    /// 0 for the first Elf of the pair, 1 for the second
+    pub side: usize,
+    pub sections: Interval<u16>,
+    /// Most Elves, including this one, assigned to any one of its sections
+    pub max_depth: usize,
+    /// Whether every one of its sections is also assigned to another Elf
+    pub redundant: bool,
+}
+
+/// Which sections are cleaned by all Elves together, and how often.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coverage {
+    /// Every section assigned to at least one Elf
+    pub covered: IntervalSet<u16>,
+    /// Number of Elves assigned to each section, as runs of equal depth.
+    /// Sections nobody is assigned are left out.
+    pub depth: Vec<(Interval<u16>, usize)>,
+    pub elves: Vec<ElfCoverage>,
+}
+
+impl Coverage {
+    pub fn new(pairs: &[Assignment]) -> Self {
+        let assignments = pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (first, second))| [(index, 0, *first), (index, 1, *second)])
+            .collect::<Vec<_>>();
+
+        let covered = assignments
+            .iter()
+            .map(|&(_, _, sections)| sections)
+            .collect();
+        let depth = depth_profile(assignments.iter().map(|&(_, _, sections)| sections));
+
+        let elves = assignments
+            .into_iter()
+            .map(|(index, side, sections)| {
+                // Runs are sorted and disjoint, so skip those ending before the Elf starts
+                let first = depth.partition_point(|(run, _)| run.end() < sections.start());
+                let depths = depth[first..]
+                    .iter()
+                    .take_while(|(run, _)| run.start() <= sections.end())
+                    .map(|&(_, depth)| depth);
+                let (min, max) = depths.fold((usize::MAX, 0), |(min, max), depth| {
+                    (min.min(depth), max.max(depth))
+                });
+                ElfCoverage {
+                    line: index + 1,
+                    side,
+                    sections,
+                    max_depth: max,
+                    redundant: min >= 2,
+                }
+            })
+            .collect();
+
+        Self {
+            covered,
+            depth,
+            elves,
+        }
+    }
+
+    /// Number of sections assigned to at least one Elf.
+    pub fn sections(&self) -> u32 {
+        u32::try_from(self.covered.len()).expect("Every `u16` section can be counted in a `u32`")
+    }
+
+    /// Runs of sections nobody is assigned, between the lowest and highest
+    /// assigned sections.
+    pub fn gaps(&self) -> Vec<Interval<u16>> {
+        self.covered.gaps()
+    }
+
+    /// Most Elves assigned to a single section.
+    pub fn max_depth(&self) -> usize {
+        self.depth
+            .iter()
+            .map(|&(_, depth)| depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The runs of sections assigned to the most Elves.
+    pub fn deepest(&self) -> Vec<Interval<u16>> {
+        let max = self.max_depth();
+        self.depth
+            .iter()
+            .filter(|&&(_, depth)| depth == max)
+            .map(|&(run, _)| run)
+            .collect()
+    }
+
+    /// The Elves whose sections are all assigned to someone else too.
+    ///
+    /// Each is redundant on its own; removing two at once may leave a gap,
+    /// for example when both have the same sections.
+    pub fn redundant(&self) -> impl Iterator<Item = &ElfCoverage> {
+        self.elves.iter().filter(|elf| elf.redundant)
+    }
+}
+
+/// Splits the sections covered by `intervals` into runs where the number of
+/// intervals covering each section is the same.
+fn depth_profile(intervals: impl Iterator<Item = Interval<u16>>) -> Vec<(Interval<u16>, usize)> {
+    // Sweep over the boundaries, widening so a run can end at `u16::MAX`
+    let mut events = intervals
+        .flat_map(|sections| {
+            [
+                (sections.start() as u32, 1),
+                (sections.end() as u32 + 1, -1),
+            ]
+        })
+        .collect::<Vec<(u32, isize)>>();
+    events.sort_unstable();
+
+    let mut profile: Vec<(Interval<u16>, usize)> = vec![];
+    let mut depth = 0;
+    for (i, &(position, change)) in events.iter().enumerate() {
+        depth += change;
+        let next = match events.get(i + 1) {
+            Some(&(next, _)) if next > position && depth > 0 => next,
+            _ => continue,
+        };
+        let run = Interval::new(position as u16, (next - 1) as u16).unwrap();
+        match profile.last_mut() {
+            Some((last, last_depth))
+                if *last_depth == depth as usize && last.end() as u32 + 1 == position =>
+            {
+                *last = last.union(&run).unwrap();
+            }
+            _ => profile.push((run, depth as usize)),
+        }
+    }
+    profile
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |runs: &[Interval<u16>]| match runs {
+            [] => "none".to_string(),
+            runs => runs
+                .iter()
+                .map(Interval::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        writeln!(f, "Sections:  {}", self.sections())?;
+        writeln!(f, "Runs:      {}", list(self.covered.runs()))?;
+        writeln!(f, "Gaps:      {}", list(&self.gaps()))?;
+        writeln!(
+            f,
+            "Max depth: {} at {}",
+            self.max_depth(),
+            list(&self.deepest())
+        )?;
+        writeln!(
+            f,
+            "Redundant: {} of {}",
+            self.redundant().count(),
+            self.elves.len()
+        )?;
+
+        write!(
+            f,
+            "\n{:>5}  {:>4}  {:>11}  {:>5}  Redundant",
+            "Line", "Elf", "Sections", "Depth"
+        )?;
+        for elf in &self.elves {
+            write!(
+                f,
+                "\n{:>5}  {:>4}  {:>11}  {:>5}  {}",
+                elf.line,
+                elf.side + 1,
+                elf.sections.to_string(),
+                elf.max_depth,
+                if elf.redundant { "yes" } else { "no" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_4::parse;
+
+    const EXAMPLE: &str = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+    #[test]
+    fn summarises_every_assignment() {
+        let coverage = Coverage::new(&parse(EXAMPLE).unwrap());
+        assert_eq!(coverage.sections(), 8);
+        assert_eq!(coverage.covered.runs(), [Interval::new(2, 9).unwrap()]);
+        assert!(coverage.gaps().is_empty());
+        assert_eq!(coverage.max_depth(), 8);
+        assert_eq!(coverage.deepest(), [Interval::single(6)]);
+        // Only the 7-9 Elf has a section, 9, that nobody else cleans
+        let needed = coverage
+            .elves
+            .iter()
+            .filter(|elf| !elf.redundant)
+            .map(|elf| elf.sections.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(needed, ["7-9"]);
+        assert!(coverage.to_string().contains("Redundant: 11 of 12"));
+    }
+
+    #[test]
+    fn finds_gaps_and_depth_runs() {
+        let coverage = Coverage::new(&parse("1-3,2-4\n8-9,9-9").unwrap());
+        assert_eq!(coverage.gaps(), [Interval::new(5, 7).unwrap()]);
+        let depth = coverage
+            .depth
+            .iter()
+            .map(|(run, depth)| (run.to_string(), *depth))
+            .collect::<Vec<_>>();
+        let expected = [("1-1", 1), ("2-3", 2), ("4-4", 1), ("8-8", 1), ("9-9", 2)];
+        assert_eq!(depth, expected.map(|(run, depth)| (run.to_string(), depth)));
+        let redundant = coverage
+            .redundant()
+            .map(|elf| (elf.line, elf.side))
+            .collect::<Vec<_>>();
+        assert_eq!(redundant, [(2, 1)]);
+        let coverage = Coverage::new(&parse("65534-65535,65535-65535").unwrap());
+        assert_eq!(coverage.max_depth(), 2);
+    }
+
+    #[test]
+    fn counts_every_section() {
+        let coverage = Coverage::new(&parse("0-65535,0-1").unwrap());
+        assert_eq!(coverage.sections(), 65536);
+        assert_eq!(coverage.depth.last().unwrap().0.end(), u16::MAX);
+        let redundant = coverage.redundant().map(|elf| elf.side).collect::<Vec<_>>();
+        assert_eq!(redundant, [1]);
+        assert!(coverage.to_string().contains("Sections:  65536"));
+    }
+}
@@ -1,68 +1,111 @@
-#[cfg(test)]
-mod tests {
+pub mod coverage;
+
+use crate::interval::Interval;
+use crate::{ParseError, Solution};
+
+/// The section ranges assigned to a pair of elves.
+pub type Assignment = (Interval<u16>, Interval<u16>);
+
+fn parse(input: &str) -> Result<Vec<Assignment>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, row)| {
+            let interval = |range: &str| {
+                Interval::parse(Day4::DAY, range).map_err(|e| e.within(index, row, range))
+            };
+            match row.split_once(',') {
+                Some((range_1, range_2)) => Ok((interval(range_1)?, interval(range_2)?)),
+                _ => Err(ParseError::new(
+                    Day4::DAY,
+                    "Malformed input, should be a `,` delimited tuple",
+                )
+                .at(index, row, row)),
+            }
+        })
+        .collect()
+}
+
+fn count_contained(pairs: &[Assignment]) -> usize {
+    pairs
+        .iter()
+        .filter(|(range_1, range_2)| range_1.covers(range_2) || range_2.covers(range_1))
+        .count()
+}
 
-    use std::cmp::{max, min};
-    use std::ops::Range;
-
-    fn convert_to_range(range: &str) -> Range<u16> {
-        match range.split_once('-') {
-            Some((start, end)) => Range {
-                start: start.parse().expect("Failed to parse to u16"),
-                end: end.parse().expect("Failed to parse to u16"),
-            },
-            _ => unreachable!("Malformed input, should be a `-` delimited tuple"),
-        }
+fn count_overlapping(pairs: &[Assignment]) -> usize {
+    pairs
+        .iter()
+        .filter(|(range_1, range_2)| range_1.overlaps(range_2))
+        .count()
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Assignment>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input.trim_end())
+    }
+
+    fn part_one(pairs: &Self::Input) -> Self::PartOne {
+        count_contained(pairs)
     }
 
-    fn intersection(range_1: &Range<u16>, range_2: &Range<u16>) -> Option<Range<u16>> {
-        match (range_2.start > range_1.end) | (range_1.start > range_2.end) {
-            true => None,
-            false => Some(max(range_1.start, range_2.start)..min(range_1.end, range_2.end)),
-        }
+    fn part_two(pairs: &Self::Input) -> Self::PartTwo {
+        count_overlapping(pairs)
     }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_4_challenge_1() {
         let input = include_str!("input").trim();
 
-        let result = input
-            .split('\n')
-            .filter_map(|row| {
-                match row.split_once(',') {
-                    Some((range_1, range_2)) => {
-                        let range_1 = convert_to_range(range_1);
-                        let range_2 = convert_to_range(range_2);
-                        intersection(&range_1, &range_2)
-                            .map(|int| (int == range_1) | (int == range_2))
-                    }
-                    _ => unreachable!("Malformed input, should be a `,` delimited tuple"),
-                }
-                .map(|result| result as u16)
-            })
-            .sum::<u16>();
-
-        assert_eq!(result, 605)
+        let pairs = parse(input).unwrap();
+        let result = count_contained(&pairs);
+
+        assert_answer(4, Part::One, "input", result);
     }
 
     #[test]
     fn day_4_challenge_2() {
         let input = include_str!("input").trim();
 
-        let result = input
-            .split('\n')
-            .filter_map(|row| {
-                match row.split_once(',') {
-                    Some((range_1, range_2)) => {
-                        let range_1 = convert_to_range(range_1);
-                        let range_2 = convert_to_range(range_2);
-                        intersection(&range_1, &range_2)
-                    }
-                    _ => unreachable!("Malformed input, should be a `,` delimited tuple"),
-                }
-                .map(|_| 1u16)
-            })
-            .sum::<u16>();
-
-        assert_eq!(result, 914)
+        let pairs = parse(input).unwrap();
+        let result = count_overlapping(&pairs);
+
+        assert_answer(4, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_invalid_section() {
+        let error = parse("2-4,6-8\n2-3,4-q").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.text, "q");
+    }
+
+    #[test]
+    fn counts_single_section_overlaps() {
+        let pairs = parse("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8").unwrap();
+        assert_eq!(count_contained(&pairs), 2);
+        assert_eq!(count_overlapping(&pairs), 4);
+    }
+
+    #[test]
+    fn counts_more_pairs_than_a_u16_holds() {
+        let pairs = parse(&"1-2,2-3\n".repeat(70_000)).unwrap();
+        assert_eq!(count_overlapping(&pairs), 70_000);
     }
 }
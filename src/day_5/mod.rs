@@ -1,124 +1,224 @@
-#[cfg(test)]
-mod tests {
+use crate::{ParseError, Solution};
 
-    #[derive(Debug)]
-    struct State(Vec<Vec<char>>);
+#[derive(Clone, Debug)]
+pub struct State(Vec<Vec<char>>);
 
-    impl State {
-        fn new(n: usize) -> Self {
-            Self(vec![vec![]; n])
-        }
+impl State {
+    fn new(n: usize) -> Self {
+        Self(vec![vec![]; n])
     }
+}
 
-    impl std::fmt::Display for State {
-        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-            write!(
-                f,
-                "{}",
-                self.0
-                    .iter()
-                    .filter_map(|column| column.last())
-                    .copied()
-                    .collect::<String>()
-            )
-        }
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .filter_map(|column| column.last())
+                .copied()
+                .collect::<String>()
+        )
     }
+}
 
-    fn parse_initial_state(initial_state: &str) -> State {
-        // Split off the column names
-        match initial_state.rsplit_once('\n') {
-            Some((initial_state, column_names)) => {
-                // As all columns can be empty we get the number of columns from the labels
-                let number_of_columns = column_names.split_whitespace().count();
-                let mut state = State::new(number_of_columns);
-
-                initial_state.rsplit('\n').for_each(|row| {
-                    let mut index = 0;
-                    while let Some(substring) = row.get(index..index + 3) {
-                        let mut chars = substring.chars();
-                        if let Some('[') = chars.next() {
-                            if let Some(ch) = chars.next() {
-                                state.0[index / 4].push(ch);
-                            }
+fn parse_initial_state(initial_state: &str) -> Result<State, ParseError> {
+    // Split off the column names
+    match initial_state.rsplit_once('\n') {
+        Some((initial_state, column_names)) => {
+            // As all columns can be empty we get the number of columns from the labels
+            let number_of_columns = column_names.split_whitespace().count();
+            let mut state = State::new(number_of_columns);
+
+            let rows = initial_state.lines().collect::<Vec<_>>();
+            // Stack from the bottom row upwards
+            for (index, row) in rows.iter().enumerate().rev() {
+                let mut position = 0;
+                while let Some(substring) = row.get(position..position + 3) {
+                    let mut chars = substring.chars();
+                    if let Some('[') = chars.next() {
+                        if let Some(ch) = chars.next() {
+                            state
+                                .0
+                                .get_mut(position / 4)
+                                .ok_or_else(|| {
+                                    ParseError::new(
+                                        Day5::DAY,
+                                        "Crate is outside the labelled columns",
+                                    )
+                                    .at(index, row, substring)
+                                })?
+                                .push(ch);
                         }
-                        index += 4;
                     }
-                });
-                state
+                    position += 4;
+                }
+            }
+            Ok(state)
+        }
+        _ => Err(ParseError::new(
+            Day5::DAY,
+            "Malformed input, expected crates above a row of column labels",
+        )
+        .at(0, initial_state, initial_state)),
+    }
+}
+
+/// Parses an operation, checking it against `heights`, the number of crates
+/// in each column before it runs, and updating them to match.
+fn parse_operation(
+    index: usize,
+    operation: &str,
+    heights: &mut [usize],
+) -> Result<[usize; 3], ParseError> {
+    let error =
+        |reason: &str, token: &str| ParseError::new(Day5::DAY, reason).at(index, operation, token);
+    let words = operation.split(' ').collect::<Vec<_>>();
+    match words[..] {
+        ["move", count_token, "from", from, "to", to] => {
+            let count = count_token
+                .parse::<usize>()
+                .map_err(|_| error("Failed to parse operation to usize", count_token))?;
+            let column = |label: &str| match label.parse::<usize>() {
+                Ok(column) if (1..=heights.len()).contains(&column) => Ok(column),
+                Ok(_) => Err(error("No such column", label)),
+                Err(_) => Err(error("Failed to parse operation to usize", label)),
+            };
+            let (from_column, to_column) = (column(from)?, column(to)?);
+            if count > heights[from_column - 1] {
+                return Err(error(
+                    &format!(
+                        "Cannot move {count} crates from a column of {}",
+                        heights[from_column - 1]
+                    ),
+                    count_token,
+                ));
             }
-            _ => unreachable!("Malformed input"),
+            heights[from_column - 1] -= count;
+            heights[to_column - 1] += count;
+            Ok([count, from_column, to_column])
         }
+        _ => Err(error(
+            "Expected an operation of the form `move <n> from <a> to <b>`",
+            operation,
+        )),
     }
+}
 
-    fn parse_operation(operation: &str) -> [usize; 3] {
-        operation
-            .splitn(6, ' ')
-            .skip(1)
-            .step_by(2)
-            .map(|num| num.parse::<usize>())
-            .collect::<Result<Vec<_>, _>>()
-            .expect("Failed to parse operation to usize")
-            .try_into()
-            .expect("Expected exactly 3 elements")
+fn parse(input: &str) -> Result<(State, Vec<[usize; 3]>), ParseError> {
+    match input.split_once("\n\n") {
+        Some((initial_state, operations)) => {
+            let state = parse_initial_state(initial_state)?;
+            // Operations are numbered from the first line after the blank separator
+            let offset = initial_state.lines().count() + 1;
+            let mut heights = state.0.iter().map(Vec::len).collect::<Vec<_>>();
+            let operations = operations
+                .trim_end()
+                .lines()
+                .enumerate()
+                .map(|(index, operation)| parse_operation(offset + index, operation, &mut heights))
+                .collect::<Result<_, _>>()?;
+            Ok((state, operations))
+        }
+        _ => Err(ParseError::new(
+            Day5::DAY,
+            "Malformed input, expected a blank line between the crates and the operations",
+        )),
     }
+}
 
-    fn apply_operations_1(state: &mut State, operations: &str) {
-        operations.split('\n').for_each(|operation| {
-            let operation = parse_operation(operation);
-            for _ in 0..operation[0] {
-                match state.0[operation[1] - 1].pop() {
-                    Some(cr) => state.0[operation[2] - 1].push(cr),
-                    None => continue,
-                }
+fn apply_operations_1(state: &mut State, operations: &[[usize; 3]]) {
+    operations.iter().for_each(|operation| {
+        for _ in 0..operation[0] {
+            match state.0[operation[1] - 1].pop() {
+                Some(cr) => state.0[operation[2] - 1].push(cr),
+                None => continue,
             }
-        });
-    }
+        }
+    });
+}
+
+fn apply_operations_2(state: &mut State, operations: &[[usize; 3]]) {
+    operations.iter().for_each(|operation| {
+        let split_index = state.0[operation[1] - 1].len() - operation[0];
 
-    fn apply_operations_2(state: &mut State, operations: &str) {
-        operations.split('\n').for_each(|operation| {
-            let operation = parse_operation(operation);
+        let mut popped = state.0[operation[1] - 1].split_off(split_index);
 
-            let split_index = state.0[operation[1] - 1].len() - operation[0];
+        state.0[operation[2] - 1].append(&mut popped);
+    });
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = (State, Vec<[usize; 3]>);
+    type PartOne = String;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
 
-            let mut popped = state.0[operation[1] - 1].split_off(split_index);
+    fn part_one((state, operations): &Self::Input) -> Self::PartOne {
+        let mut state = state.clone();
+        apply_operations_1(&mut state, operations);
+        state.to_string()
+    }
 
-            state.0[operation[2] - 1].append(&mut popped);
-        });
+    fn part_two((state, operations): &Self::Input) -> Self::PartTwo {
+        let mut state = state.clone();
+        apply_operations_2(&mut state, operations);
+        state.to_string()
     }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_5_challenge_1() {
         let input = include_str!("input");
 
-        let output = match input.split_once("\n\n") {
-            Some((initial_state, operations)) => {
-                let mut state = parse_initial_state(initial_state);
+        let (mut state, operations) = parse(input).unwrap();
+        apply_operations_1(&mut state, &operations);
+        let output = state.to_string();
 
-                let operations = operations.trim();
-                apply_operations_1(&mut state, operations);
-                state.to_string()
-            }
-            _ => unreachable!("Malformed input"),
-        };
-
-        assert_eq!(output, "HNSNMTLHQ");
+        assert_answer(5, Part::One, "input", output);
     }
 
     #[test]
     fn day_5_challenge_2() {
         let input = include_str!("input");
 
-        let output = match input.split_once("\n\n") {
-            Some((initial_state, operations)) => {
-                let mut state = parse_initial_state(initial_state);
+        let (mut state, operations) = parse(input).unwrap();
+        apply_operations_2(&mut state, &operations);
+        let output = state.to_string();
 
-                let operations = operations.trim();
-                apply_operations_2(&mut state, operations);
-                state.to_string()
-            }
-            _ => unreachable!("Malformed input"),
-        };
+        assert_answer(5, Part::Two, "input", output);
+    }
 
-        assert_eq!(output, "RNLFDJMCT");
+    #[test]
+    fn reports_unknown_column() {
+        let input = "[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 1 from 2 to 3";
+        let error = parse(input).unwrap_err();
+        assert_eq!((error.line, error.column), (5, 18));
+        assert_eq!(error.text, "3");
+    }
+
+    #[test]
+    fn reports_moving_too_many_crates() {
+        let input = "[A]\n[B] [C]\n 1   2 \n\nmove 1 from 2 to 1\nmove 4 from 1 to 2";
+        let error = parse(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (6, 6, "4"));
+        assert_eq!(error.reason, "Cannot move 4 crates from a column of 3");
+        let error = parse("[A]\n 1 \n\nmove 1 from 0 to 1").unwrap_err();
+        assert_eq!(error.text, "0");
     }
 }
@@ -1,5 +1,9 @@
-fn marker(signal: &[char], marker_length: usize) -> usize {
-    match signal
+use crate::{ParseError, Solution};
+
+/// The number of characters read up to the end of the first run of
+/// `marker_length` distinct characters.
+fn marker(signal: &[char], marker_length: usize) -> Option<usize> {
+    signal
         .windows(marker_length)
         .enumerate()
         .find(|(_, chars)| {
@@ -7,9 +11,30 @@ fn marker(signal: &[char], marker_length: usize) -> usize {
             chars.sort();
             chars.dedup();
             chars.len() == marker_length
-        }) {
-        Some((index, _)) => index + marker_length,
-        _ => unreachable!("Malformed Input"),
+        })
+        .map(|(index, _)| index + marker_length)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Vec<char>;
+    /// `None` if the datastream has no marker.
+    type PartOne = Option<usize>;
+    type PartTwo = Option<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.trim().chars().collect())
+    }
+
+    fn part_one(signal: &Self::Input) -> Self::PartOne {
+        marker(signal, 4)
+    }
+
+    fn part_two(signal: &Self::Input) -> Self::PartTwo {
+        marker(signal, 14)
     }
 }
 
@@ -17,112 +42,122 @@ fn marker(signal: &[char], marker_length: usize) -> usize {
 mod tests {
 
     use super::*;
+    use crate::verify::assert_answer;
+    use crate::{Answer, Part};
 
     #[test]
     fn day_6_challenge_1_test_1() {
         let input = include_str!("test1").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 4);
+        let marker_position = marker(&input, 4).unwrap();
 
-        assert_eq!(marker_position, 7);
+        assert_answer(6, Part::One, "test1", marker_position);
     }
 
     #[test]
     fn day_6_challenge_1_test_2() {
         let input = include_str!("test2").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 4);
+        let marker_position = marker(&input, 4).unwrap();
 
-        assert_eq!(marker_position, 5);
+        assert_answer(6, Part::One, "test2", marker_position);
     }
 
     #[test]
     fn day_6_challenge_1_test_3() {
         let input = include_str!("test3").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 4);
+        let marker_position = marker(&input, 4).unwrap();
 
-        assert_eq!(marker_position, 6);
+        assert_answer(6, Part::One, "test3", marker_position);
     }
 
     #[test]
     fn day_6_challenge_1_test_4() {
         let input = include_str!("test4").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 4);
+        let marker_position = marker(&input, 4).unwrap();
 
-        assert_eq!(marker_position, 10);
+        assert_answer(6, Part::One, "test4", marker_position);
     }
 
     #[test]
     fn day_6_challenge_1_test_5() {
         let input = include_str!("test5").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 4);
+        let marker_position = marker(&input, 4).unwrap();
 
-        assert_eq!(marker_position, 11);
+        assert_answer(6, Part::One, "test5", marker_position);
     }
 
     #[test]
     fn day_6_challenge_1() {
         let input = include_str!("input").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 4);
+        let marker_position = marker(&input, 4).unwrap();
 
-        assert_eq!(marker_position, 1538);
+        assert_answer(6, Part::One, "input", marker_position);
     }
 
     #[test]
     fn day_6_challenge_2_test_1() {
         let input = include_str!("test1").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 14);
+        let marker_position = marker(&input, 14).unwrap();
 
-        assert_eq!(marker_position, 19);
+        assert_answer(6, Part::Two, "test1", marker_position);
     }
 
     #[test]
     fn day_6_challenge_2_test_2() {
         let input = include_str!("test2").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 14);
+        let marker_position = marker(&input, 14).unwrap();
 
-        assert_eq!(marker_position, 23);
+        assert_answer(6, Part::Two, "test2", marker_position);
     }
 
     #[test]
     fn day_6_challenge_2_test_3() {
         let input = include_str!("test3").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 14);
+        let marker_position = marker(&input, 14).unwrap();
 
-        assert_eq!(marker_position, 23);
+        assert_answer(6, Part::Two, "test3", marker_position);
     }
 
     #[test]
     fn day_6_challenge_2_test_4() {
         let input = include_str!("test4").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 14);
+        let marker_position = marker(&input, 14).unwrap();
 
-        assert_eq!(marker_position, 29);
+        assert_answer(6, Part::Two, "test4", marker_position);
     }
 
     #[test]
     fn day_6_challenge_2_test_5() {
         let input = include_str!("test5").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 14);
+        let marker_position = marker(&input, 14).unwrap();
 
-        assert_eq!(marker_position, 26);
+        assert_answer(6, Part::Two, "test5", marker_position);
     }
 
     #[test]
     fn day_6_challenge_2() {
         let input = include_str!("input").trim().chars().collect::<Vec<char>>();
 
-        let marker_position = marker(&input, 14);
+        let marker_position = marker(&input, 14).unwrap();
 
-        assert_eq!(marker_position, 2315);
+        assert_answer(6, Part::Two, "input", marker_position);
+    }
+
+    #[test]
+    fn reports_a_missing_marker() {
+        let signal = Day6::parse("abcdabcdabcd").unwrap();
+        assert_eq!(Day6::part_one(&signal), Some(4));
+        assert_eq!(Day6::part_two(&signal), None);
+        assert_eq!(Answer::from(Day6::part_two(&signal)).to_string(), "none");
     }
 }
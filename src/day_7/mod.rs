@@ -1,7 +1,9 @@
 use std::ops::AddAssign;
 
+use crate::{ParseError, Solution};
+
 #[derive(Debug, Default)]
-struct Tree<T>
+pub struct Tree<T>
 where
     T: PartialEq,
 {
@@ -37,11 +39,10 @@ where
 }
 
 #[derive(Debug)]
-struct Node<T>
+pub struct Node<T>
 where
     T: PartialEq,
 {
-    idx: usize,
     label: String,
     size: T,
     parent: Option<usize>,
@@ -52,9 +53,8 @@ impl<T> Node<T>
 where
     T: PartialEq,
 {
-    fn new(idx: usize, label: &str, size: T) -> Self {
+    fn new(label: &str, size: T) -> Self {
         Self {
-            idx,
             label: label.to_string(),
             size,
             parent: None,
@@ -70,28 +70,43 @@ enum Line<'a> {
     File((&'a str, u32)),
 }
 
-fn parse_line(line: &'_ str) -> Option<Line<'_>> {
+fn parse_line(index: usize, line: &'_ str) -> Result<Option<Line<'_>>, ParseError> {
+    let error =
+        |reason: &str, token: &str| ParseError::new(Day7::DAY, reason).at(index, line, token);
     match line.split_once(' ') {
+        Some(("$", "ls")) => Ok(None),
         Some(("$", command)) => match command.split_once(' ') {
-            Some(("cd", dir)) => Some(Line::ChangeDir(dir)),
-            _ => None,
+            Some(("cd", dir)) if !dir.is_empty() => Ok(Some(Line::ChangeDir(dir))),
+            _ if command.trim_end() == "cd" => {
+                Err(error("Expected a directory to change to", command))
+            }
+            _ => {
+                let name = command.split(' ').next().unwrap_or(command);
+                Err(error("Unknown command, expected `cd` or `ls`", name))
+            }
         },
-        Some(("dir", name)) => Some(Line::Dir(name)),
-        Some((size, name)) => Some(Line::File((
+        Some(("dir", name)) => Ok(Some(Line::Dir(name))),
+        Some((size, name)) => Ok(Some(Line::File((
             name,
-            size.parse::<u32>().expect("Failed to parse filesize"),
-        ))),
-        _ => unreachable!("Malformed Input"),
+            size.parse::<u32>()
+                .map_err(|_| error("Failed to parse filesize", size))?,
+        )))),
+        _ => Err(error(
+            "Malformed Input, expected a command, directory or file",
+            line,
+        )),
     }
 }
 
-fn process_input(input: &str) -> Tree<u32> {
+fn process_input(input: &str) -> Result<Tree<u32>, ParseError> {
     let mut file_system: Tree<u32> = Tree { nodes: vec![] };
-    file_system.insert(Node::new(0, "/", 0u32));
+    file_system.insert(Node::new("/", 0u32));
     let mut node_index = 0;
 
-    for line in input.lines() {
-        match parse_line(line) {
+    for (index, line) in input.lines().enumerate() {
+        let error =
+            |reason: &str, dir: &str| ParseError::new(Day7::DAY, reason).at(index, line, dir);
+        match parse_line(index, line)? {
             Some(Line::ChangeDir(dir)) => match dir {
                 "/" => node_index = 0,
                 ".." => {
@@ -99,7 +114,7 @@ fn process_input(input: &str) -> Tree<u32> {
                         Some(parent_index) => {
                             node_index = parent_index;
                         }
-                        None => unreachable!("No parent registered..."),
+                        None => return Err(error("No parent registered", dir)),
                     };
                 }
                 dir => {
@@ -107,99 +122,156 @@ fn process_input(input: &str) -> Tree<u32> {
                         Some(child_index) => {
                             node_index = child_index;
                         }
-                        None => unreachable!("No child registered..."),
+                        None => return Err(error("No child registered", dir)),
                     };
                 }
             },
             Some(Line::Dir(dir_name)) => {
                 let new_node_index = file_system.nodes.len();
-                let mut node = Node::new(new_node_index, dir_name, 0u32);
+                let mut node = Node::new(dir_name, 0u32);
                 node.parent = Some(node_index);
                 file_system.insert(node);
                 file_system.nodes[node_index].children.push(new_node_index);
             }
             Some(Line::File((_, size))) => {
+                // The root holds every file, so no directory can outgrow it
+                let root_size = file_system.nodes[0].size.checked_add(size);
+                if root_size.is_none_or(|root_size| root_size > FILE_SYSTEM_SIZE) {
+                    return Err(error(
+                        &format!("Files take up more than the {FILE_SYSTEM_SIZE} available"),
+                        line,
+                    ));
+                }
                 file_system.register_file(node_index, size);
             }
             None => {}
         }
     }
+    Ok(file_system)
+}
+
+const FILE_SYSTEM_SIZE: u32 = 70000000;
+const REQUIRED_SPACE: u32 = 30000000;
+
+fn sum_of_small_directories(file_system: &Tree<u32>) -> u32 {
+    file_system
+        .nodes
+        .iter()
+        .filter(|node| node.size <= 100000)
+        .fold(0u32, |a, b| a + b.size)
+}
+
+fn smallest_directory_to_delete(file_system: &Tree<u32>) -> u32 {
+    let root_size = file_system.nodes[0].size;
+    let deficit = REQUIRED_SPACE.saturating_sub(FILE_SYSTEM_SIZE - root_size);
+    if deficit == 0 {
+        return 0;
+    }
     file_system
+        .nodes
+        .iter()
+        .map(|node| node.size)
+        .filter(|&size| size >= deficit)
+        .min()
+        .expect("The root directory is larger than the deficit")
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Tree<u32>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        process_input(input.trim_end())
+    }
+
+    fn part_one(file_system: &Self::Input) -> Self::PartOne {
+        sum_of_small_directories(file_system)
+    }
+
+    fn part_two(file_system: &Self::Input) -> Self::PartTwo {
+        smallest_directory_to_delete(file_system)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_7_test_1() {
         let input = include_str!("test").trim();
 
-        let file_system = process_input(input);
+        let file_system = process_input(input).unwrap();
 
-        let result = file_system
-            .nodes
-            .iter()
-            .filter(|node| node.size <= 100000)
-            .fold(0u32, |a, b| a + b.size);
+        let result = sum_of_small_directories(&file_system);
 
-        assert_eq!(result, 95437u32);
+        assert_answer(7, Part::One, "test", result);
     }
 
     #[test]
     fn day_7_challenge_1() {
         let input = include_str!("input").trim();
 
-        let file_system = process_input(input);
+        let file_system = process_input(input).unwrap();
 
-        let result = file_system
-            .nodes
-            .iter()
-            .filter(|node| node.size <= 100000)
-            .fold(0u32, |a, b| a + b.size);
+        let result = sum_of_small_directories(&file_system);
 
-        assert_eq!(result, 1453349u32);
+        assert_answer(7, Part::One, "input", result);
     }
 
     #[test]
     fn day_7_test_2() {
         let input = include_str!("test").trim();
 
-        let file_system = process_input(input);
+        let file_system = process_input(input).unwrap();
 
-        let file_system_size = 70000000;
-        let required_space = 30000000;
-        let root_size = file_system.nodes[0].size;
-        let deficit = required_space - (file_system_size - root_size);
-
-        let mut possible_nodes = file_system
-            .nodes
-            .iter()
-            .filter(|node| node.size >= deficit)
-            .collect::<Vec<_>>();
-
-        possible_nodes.sort_by(|a, b| b.size.cmp(&a.size));
-        let result = possible_nodes.pop().unwrap().size;
-        assert_eq!(result, 24933642u32);
+        let result = smallest_directory_to_delete(&file_system);
+        assert_answer(7, Part::Two, "test", result);
     }
 
     #[test]
     fn day_7_challenge_2() {
         let input = include_str!("input").trim();
 
-        let file_system = process_input(input);
-
-        let file_system_size = 70000000;
-        let required_space = 30000000;
-        let root_size = file_system.nodes[0].size;
-        let deficit = required_space - (file_system_size - root_size);
-        let mut possible_nodes = file_system
-            .nodes
-            .into_iter()
-            .filter(|node| node.size >= deficit)
-            .collect::<Vec<_>>();
-        possible_nodes.sort_by(|a, b| b.size.cmp(&a.size));
-        let result = possible_nodes.pop().unwrap().size;
-        assert_eq!(result, 2948823u32);
+        let file_system = process_input(input).unwrap();
+
+        let result = smallest_directory_to_delete(&file_system);
+        assert_answer(7, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_unknown_directory() {
+        let input = "$ cd /\n$ ls\ndir a\n$ cd b";
+        let error = process_input(input).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 6));
+        assert_eq!(error.text, "b");
+    }
+
+    #[test]
+    fn reports_unknown_commands() {
+        let error = process_input("$ cd /\n$ rm x").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 3, "rm")
+        );
+        let error = process_input("$ cd /\n$ cd").unwrap_err();
+        assert_eq!(error.reason, "Expected a directory to change to");
+        assert!(process_input("$ ls -a").is_err());
+    }
+
+    #[test]
+    fn handles_small_and_oversized_file_systems() {
+        let file_system = process_input("$ cd /\n$ ls\n100 a.txt").unwrap();
+        assert_eq!(smallest_directory_to_delete(&file_system), 0);
+        let input = "$ cd /\n$ ls\n40000000 a.txt\n40000000 b.txt";
+        let error = process_input(input).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
     }
 }
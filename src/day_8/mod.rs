@@ -1,259 +1,154 @@
-use std::cmp::Ordering;
-use std::collections::BTreeMap;
-use std::ops::Range;
-
-#[derive(Debug, Hash, PartialEq, Eq)]
-struct Coordinate {
-    x: usize,
-    y: usize,
-}
-
-impl Ord for Coordinate {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (self.x, self.y).cmp(&(other.x, other.y))
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::render::{Cell, Frame, Render, Rgb};
+use crate::{ParseError, Solution};
+
+/// The height of every tree in the forest.
+pub struct Forest(Grid<u8>);
+
+/// Shades each tree by its height, highlighting those visible from outside the forest.
+impl Render for Forest {
+    fn render(&self) -> Frame {
+        Grid::from_fn(self.0.width(), self.0.height(), |position| {
+            let height = self.0[position];
+            let colour = match is_visible(&self.0, position) {
+                true => Rgb::new(60, 90, 0).blend(Rgb::new(230, 255, 80), height as u32, 9),
+                false => Rgb::new(0, 30, 0).blend(Rgb::new(0, 140, 40), height as u32, 9),
+            };
+            Cell::new(char::from(b'0' + height), colour)
+        })
     }
 }
 
-impl PartialOrd for Coordinate {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+    let trees = Grid::parse(Day8::DAY, input, |_, ch| {
+        ch.to_digit(10)
+            .map(|height| height as u8)
+            .ok_or("Tree heights must be single digits")
+    })?;
+    match trees.width() * trees.height() {
+        0 => Err(ParseError::new(Day8::DAY, "Expected at least one tree")),
+        _ => Ok(trees),
     }
 }
 
-fn parse(input: &str) -> BTreeMap<Coordinate, u8> {
-    let mut items = BTreeMap::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, ch)| {
-            items.insert(Coordinate { x, y }, ch as u8 - 48);
-        })
-    });
-
-    items
+/// The trees between `position` and the edge of the forest, looking in `direction`.
+fn line_of_sight(
+    trees: &Grid<u8>,
+    position: Point<usize>,
+    direction: Point<isize>,
+) -> impl Iterator<Item = u8> + '_ {
+    trees.ray(position, direction).skip(1).copied()
 }
 
-fn visible_from_one_direction_x(
-    range: Range<usize>,
-    y: usize,
-    height: u8,
-    trees: &BTreeMap<Coordinate, u8>,
-) -> bool {
-    range
-        .map(|x| Coordinate { x, y })
-        .map(|c| trees[&c])
-        .all(|other_height| other_height < height)
+fn is_visible(trees: &Grid<u8>, position: Point<usize>) -> bool {
+    let height = trees[position];
+    Point::ORTHOGONAL.iter().any(|&direction| {
+        line_of_sight(trees, position, direction).all(|other_height| other_height < height)
+    })
 }
 
-fn visible_from_one_direction_y(
-    range: Range<usize>,
-    x: usize,
-    height: u8,
-    trees: &BTreeMap<Coordinate, u8>,
-) -> bool {
-    range
-        .map(|y| Coordinate { x, y })
-        .map(|c| trees[&c])
-        .all(|other_height| other_height < height)
+fn visibility(trees: &Grid<u8>) -> usize {
+    trees
+        .positions()
+        .filter(|&position| is_visible(trees, position))
+        .count()
 }
 
-fn is_on_edge(
-    coordinate: &Coordinate,
-    Coordinate {
-        x: corner_x,
-        y: corner_y,
-    }: &Coordinate,
-) -> bool {
-    match coordinate {
-        Coordinate { x: 0, .. } => true,
-        Coordinate { y: 0, .. } => true,
-        Coordinate { x, .. } if x == corner_x => true,
-        Coordinate { y, .. } if y == corner_y => true,
-        _ => false,
+fn viewing_distance(trees: &Grid<u8>, position: Point<usize>, direction: Point<isize>) -> usize {
+    let height = trees[position];
+    let mut distance = 0;
+    for other_height in line_of_sight(trees, position, direction) {
+        distance += 1;
+        if other_height >= height {
+            break;
+        }
     }
+    distance
 }
 
-fn visibility(trees: &BTreeMap<Coordinate, u8>) -> usize {
-    let last_coordinate = trees.last_key_value().expect("Empty tree").0;
-    trees.iter().fold(0, |sum, (coordinate, &height)| {
-        let visible = if is_on_edge(coordinate, last_coordinate) {
-            true
-        } else {
-            visible_from_one_direction_x(0..coordinate.x, coordinate.y, height, trees)
-                || visible_from_one_direction_x(
-                    (coordinate.x + 1)..(last_coordinate.x + 1),
-                    coordinate.y,
-                    height,
-                    trees,
-                )
-                || visible_from_one_direction_y(0..coordinate.y, coordinate.x, height, trees)
-                || visible_from_one_direction_y(
-                    (coordinate.y + 1)..(last_coordinate.y + 1),
-                    coordinate.x,
-                    height,
-                    trees,
-                )
-        };
-
-        sum + visible as usize
-    })
+fn scenic_score(trees: &Grid<u8>) -> usize {
+    trees
+        .positions()
+        .map(|position| {
+            Point::ORTHOGONAL
+                .iter()
+                .map(|&direction| viewing_distance(trees, position, direction))
+                .product()
+        })
+        .max()
+        .expect("Parsing checks the forest has a tree")
 }
 
-fn find_max<I>(iter: I) -> Option<I::Item>
-where
-    I: Iterator,
-    I::Item: Ord,
-{
-    iter.reduce(|accum, item| if accum >= item { accum } else { item })
-}
+pub struct Day8;
 
-fn scenic_score_x(
-    Range { start, end }: Range<usize>,
-    y: usize,
-    height: u8,
-    trees: &BTreeMap<Coordinate, u8>,
-) -> usize {
-    let n = if start < end {
-        end - start
-    } else {
-        start - end
-    };
-    let range = if start < end {
-        Box::new(start..end) as Box<dyn Iterator<Item = _>>
-    } else {
-        Box::new((end..start).rev())
-    };
-    let mut last_height = 0;
-    match range
-        .map(|x| Coordinate { x, y })
-        .map(|c| trees[&c])
-        .take_while(|&other_height| {
-            last_height = other_height;
-            other_height < height
-        })
-        .count()
-    {
-        0 => 1,
-        x if x == n => {
-            if last_height == height {
-                x + 1
-            } else {
-                x
-            }
-        }
-        x => {
-            if last_height == height {
-                x + 1
-            } else {
-                x + 1
-            }
-        }
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Forest;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input.trim_end()).map(Forest)
     }
-}
 
-fn scenic_score_y(
-    Range { start, end }: Range<usize>,
-    x: usize,
-    height: u8,
-    trees: &BTreeMap<Coordinate, u8>,
-) -> usize {
-    let n = if start < end {
-        end - start
-    } else {
-        start - end
-    };
-    let range = if start < end {
-        Box::new(start..end) as Box<dyn Iterator<Item = _>>
-    } else {
-        Box::new((end..start).rev())
-    };
-    let mut last_height = 0;
-    match range
-        .map(|y| Coordinate { x, y })
-        .map(|c| trees[&c])
-        .take_while(|&other_height| {
-            last_height = other_height;
-            other_height < height
-        })
-        .count()
-    {
-        0 => 1,
-        x if x == n => {
-            if last_height == height {
-                x + 1
-            } else {
-                x
-            }
-        }
-        x => {
-            if last_height == height {
-                x + 1
-            } else {
-                x + 1
-            }
-        }
+    fn part_one(Forest(trees): &Self::Input) -> Self::PartOne {
+        visibility(trees)
     }
-}
 
-fn scenic_score(trees: &BTreeMap<Coordinate, u8>) -> usize {
-    let last_coordinate = trees.last_key_value().expect("Empty tree").0;
+    fn part_two(Forest(trees): &Self::Input) -> Self::PartTwo {
+        scenic_score(trees)
+    }
 
-    find_max(trees.iter().map(|(coordinate, &height)| {
-        let score = if is_on_edge(coordinate, &last_coordinate) {
-            0
-        } else {
-            scenic_score_x((coordinate.x)..0, coordinate.y, height, trees)
-                * scenic_score_x(
-                    (coordinate.x + 1)..(last_coordinate.x + 1),
-                    coordinate.y,
-                    height,
-                    trees,
-                )
-                * scenic_score_y((coordinate.y)..0, coordinate.x, height, trees)
-                * scenic_score_y(
-                    (coordinate.y + 1)..(last_coordinate.y + 1),
-                    coordinate.x,
-                    height,
-                    trees,
-                )
-        };
-        score
-    }))
-    .expect("The scores were empty...")
+    fn render(forest: &Self::Input) -> Option<Frame> {
+        Some(forest.render())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
 
     #[test]
     fn day_8_test_1() {
         let input = include_str!("test").trim();
-        let trees = parse(input);
+        let trees = parse(input).unwrap();
         let score = visibility(&trees);
-        assert_eq!(score, 21);
+        assert_answer(8, Part::One, "test", score);
     }
 
     #[test]
     fn day_8_challenge_1() {
         let input = include_str!("input").trim();
-        let trees = parse(input);
+        let trees = parse(input).unwrap();
         let score = visibility(&trees);
-        assert_eq!(score, 1840);
+        assert_answer(8, Part::One, "input", score);
     }
 
     #[test]
     fn day_8_test_2() {
         let input = include_str!("test").trim();
-        let trees = parse(input);
+        let trees = parse(input).unwrap();
         let score = scenic_score(&trees);
-        assert_eq!(score, 8);
+        assert_answer(8, Part::Two, "test", score);
     }
 
     #[test]
     fn day_8_challenge_2() {
         let input = include_str!("input").trim();
-        let trees = parse(input);
+        let trees = parse(input).unwrap();
         let score = scenic_score(&trees);
-        assert_eq!(score, 405769);
+        assert_answer(8, Part::Two, "input", score);
+    }
+
+    #[test]
+    fn rejects_an_empty_forest() {
+        assert!(Day8::parse("").is_err());
+        assert!(Day8::parse("\n\n").is_err());
+        assert_eq!(scenic_score(&parse("5").unwrap()), 0);
     }
 }
@@ -1,144 +1,246 @@
-#[cfg(test)]
-mod tests {
+use std::collections::BTreeSet;
+
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::render::{Cell, Frame, Render, Rgb};
+use crate::{ParseError, Solution};
+
+#[derive(Clone, Debug)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
 
-    use std::{cmp::Ordering, collections::BTreeSet, ops::Sub};
+impl Direction {
+    fn step(&self) -> Point<i32> {
+        match self {
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+            Direction::Up => Point::new(0, 1),
+            Direction::Down => Point::new(0, -1),
+        }
+    }
+}
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    struct Coordinate {
-        x: i16,
-        y: i16,
+/// A direction and how many steps the head takes in it.
+pub type Move = (Direction, usize);
+
+/// Moves `knot` a step towards `last` once they are no longer touching.
+fn follow(knot: Point<i32>, last: Point<i32>) -> Point<i32> {
+    if knot.chebyshev(&last) >= 2 {
+        knot.step_towards(&last)
+    } else {
+        knot
     }
+}
 
-    impl Ord for Coordinate {
-        fn cmp(&self, other: &Self) -> Ordering {
-            (self.y, self.x).cmp(&(other.y, other.x))
+fn parse(input: &str) -> Result<Vec<Move>, ParseError> {
+    let mut moves = vec![];
+    // Where the head has got to, widened so leaving the range of `i32` is caught
+    let mut head = Point::<i64>::default();
+    for (index, line) in input.lines().enumerate() {
+        let error =
+            |reason: &str, token: &str| ParseError::new(Day9::DAY, reason).at(index, line, token);
+        match line.split_once(' ') {
+            Some((direction, count)) => {
+                let repeats = count
+                    .parse::<usize>()
+                    .map_err(|_| error("Repeats can't be parsed", count))?;
+                let direction = match direction.to_uppercase().as_str() {
+                    "L" => Direction::Left,
+                    "R" => Direction::Right,
+                    "U" => Direction::Up,
+                    "D" => Direction::Down,
+                    _ => return Err(error("Expected one of `L`, `R`, `U` or `D`", direction)),
+                };
+                let step = direction.step();
+                let distance = i64::try_from(repeats).unwrap_or(i64::MAX);
+                head = Point::new(
+                    head.x.saturating_add(step.x as i64 * distance),
+                    head.y.saturating_add(step.y as i64 * distance),
+                );
+                if i32::try_from(head.x).is_err() || i32::try_from(head.y).is_err() {
+                    return Err(error("The head moves too far from the start", count));
+                }
+                moves.push((direction, repeats));
+            }
+            _ => {
+                return Err(error(
+                    "Malformed input, expected a direction and a count",
+                    line,
+                ))
+            }
         }
     }
+    Ok(moves)
+}
 
-    impl PartialOrd for Coordinate {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            Some(self.cmp(other))
-        }
+/// The position of every knot, and every position the tail has visited.
+pub struct Rope {
+    knots: Vec<Point<i32>>,
+    visited: BTreeSet<Point<i32>>,
+}
+
+impl Rope {
+    fn new(number_of_knots: usize) -> Self {
+        let knots = vec![Point::default(); number_of_knots];
+        let visited = BTreeSet::from([*knots.last().unwrap()]);
+        Self { knots, visited }
     }
 
-    impl Sub for Coordinate {
-        type Output = Self;
+    fn pull(&mut self, direction: &Direction) {
+        // Head
+        self.knots[0] = self.knots[0] + direction.step();
 
-        fn sub(self, other: Self) -> Self::Output {
-            Self {
-                x: self.x - other.x,
-                y: self.y - other.y,
-            }
+        for knot in 1..self.knots.len() {
+            self.knots[knot] = follow(self.knots[knot], self.knots[knot - 1]);
         }
-    }
 
-    #[derive(Clone, Debug)]
-    enum Direction {
-        Left,
-        Right,
-        Up,
-        Down,
+        self.visited.insert(*self.knots.last().unwrap());
     }
+}
 
-    impl Coordinate {
-        fn move_in(&mut self, direction: &Direction) {
-            match direction {
-                Direction::Left => self.x -= 1,
-                Direction::Right => self.x += 1,
-                Direction::Up => self.y += 1,
-                Direction::Down => self.y -= 1,
-            }
+/// Draws the visited positions and the knots, with up at the top.
+impl Render for Rope {
+    fn render(&self) -> Frame {
+        let points = || self.visited.iter().chain(&self.knots);
+        let min = Point::new(
+            points().map(|p| p.x).min().unwrap(),
+            points().map(|p| p.y).min().unwrap(),
+        );
+        let max = Point::new(
+            points().map(|p| p.x).max().unwrap(),
+            points().map(|p| p.y).max().unwrap(),
+        );
+        let size = max - min;
+        let mut frame = Grid::new(
+            size.x as usize + 1,
+            size.y as usize + 1,
+            Cell::new('.', Rgb::new(40, 40, 40)),
+        );
+        let mut draw = |point: Point<i32>, cell| {
+            frame[Point::new((point.x - min.x) as usize, (max.y - point.y) as usize)] = cell;
+        };
+        for &point in &self.visited {
+            draw(point, Cell::new('#', Rgb::new(90, 130, 200)));
         }
-
-        fn follow(&mut self, last: &Self) {
-            if (last.x - self.x).abs() >= 2 || (last.y - self.y).abs() >= 2 {
-                self.x += (last.x - self.x).signum();
-                self.y += (last.y - self.y).signum();
-            }
+        draw(Point::default(), Cell::new('s', Rgb::WHITE));
+        // Draw from the tail forwards so knots nearer the head stay on top
+        for (index, &knot) in self.knots.iter().enumerate().rev() {
+            let glyph = match index {
+                0 => 'H',
+                index => char::from_digit(index as u32 % 10, 10).unwrap(),
+            };
+            draw(knot, Cell::new(glyph, Rgb::new(255, 180, 60)));
         }
+        frame
     }
+}
 
-    fn parse(input: &str) -> Vec<Direction> {
-        input
-            .lines()
-            .map(|line| match line.split_once(" ") {
-                Some((direction, repeats)) => {
-                    let repeats = repeats.parse::<usize>().expect("Repeats can't be parsed");
-                    vec![
-                        match direction.to_uppercase().as_str() {
-                            "L" => Direction::Left,
-                            "R" => Direction::Right,
-                            "U" => Direction::Up,
-                            "D" => Direction::Down,
-                            _ => unreachable!("Malformed Input"),
-                        };
-                        repeats
-                    ]
-                }
-                _ => unreachable!("Malformed input"),
-            })
-            .flatten()
-            .collect()
+fn simulate(moves: &[Move], number_of_knots: usize) -> Rope {
+    let mut rope = Rope::new(number_of_knots);
+    for (direction, repeats) in moves {
+        for _ in 0..*repeats {
+            rope.pull(direction);
+        }
     }
+    rope
+}
 
-    fn process(actions: &[Direction], number_of_knots: usize) -> usize {
-        let mut positions = vec![Coordinate { x: 0, y: 0 }; number_of_knots];
-        let mut visited = BTreeSet::new();
+fn process(moves: &[Move], number_of_knots: usize) -> usize {
+    simulate(moves, number_of_knots).visited.len()
+}
 
-        visited.insert(positions.last().unwrap().clone());
+pub struct Day9;
 
-        actions.iter().for_each(|direction| {
-            // Head
-            positions[0].move_in(direction);
+impl Solution for Day9 {
+    const DAY: u8 = 9;
 
-            for knot in 1..number_of_knots {
-                let last = positions[knot - 1].clone();
-                positions[knot].follow(&last);
-            }
+    type Input = Vec<Move>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-            visited.insert(positions.last().unwrap().clone());
-        });
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input.trim_end())
+    }
+
+    fn part_one(moves: &Self::Input) -> Self::PartOne {
+        process(moves, 2)
+    }
 
-        visited.len()
+    fn part_two(moves: &Self::Input) -> Self::PartTwo {
+        process(moves, 10)
     }
 
+    /// Draws the ten-knot rope of part two after every move.
+    fn render(moves: &Self::Input) -> Option<Frame> {
+        Some(simulate(moves, 10).render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::assert_answer;
+    use crate::Part;
+
     #[test]
     fn day_9_test_1() {
         let input = include_str!("test").trim();
-        let actions = parse(input);
-        let result = process(&actions, 2);
-        assert_eq!(result, 13);
+        let moves = parse(input).unwrap();
+        let result = process(&moves, 2);
+        assert_answer(9, Part::One, "test", result);
     }
 
     #[test]
     fn day_9_challenge_1() {
         let input = include_str!("input").trim();
-        let actions = parse(input);
-        let result = process(&actions, 2);
-        assert_eq!(result, 6503);
+        let moves = parse(input).unwrap();
+        let result = process(&moves, 2);
+        assert_answer(9, Part::One, "input", result);
     }
 
     #[test]
     fn day_9_test_2() {
         let input = include_str!("test").trim();
-        let actions = parse(input);
-        let result = process(&actions, 10);
-        assert_eq!(result, 1);
+        let moves = parse(input).unwrap();
+        let result = process(&moves, 10);
+        assert_answer(9, Part::Two, "test", result);
     }
 
     #[test]
     fn day_9_test_3() {
         let input = include_str!("test2").trim();
-        let actions = parse(input);
-        let result = process(&actions, 10);
-        assert_eq!(result, 36);
+        let moves = parse(input).unwrap();
+        let result = process(&moves, 10);
+        assert_answer(9, Part::Two, "test2", result);
     }
 
     #[test]
     fn day_9_challenge_2() {
         let input = include_str!("input").trim();
-        let actions = parse(input);
-        let result = process(&actions, 10);
-        assert_eq!(result, 2724);
+        let moves = parse(input).unwrap();
+        let result = process(&moves, 10);
+        assert_answer(9, Part::Two, "input", result);
+    }
+
+    #[test]
+    fn reports_unknown_direction() {
+        let error = parse("R 4\nU 4\nX 3").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.text, "X");
+    }
+
+    #[test]
+    fn moves_far_from_the_start() {
+        let moves = parse("R 40000\nU 2").unwrap();
+        assert_eq!(process(&moves, 2), 40001);
+        // Long moves are kept whole rather than split into single steps
+        let moves = parse("R 1000000000\nL 1000000000").unwrap();
+        assert_eq!(moves.len(), 2);
+        let error = parse("R 1\nL 2147483650").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (2, "2147483650"));
     }
 }
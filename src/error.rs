@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// An error raised while parsing a puzzle input.
+///
+/// Lines and columns are 1-based and columns count characters, so they
+/// match what an editor shows for the offending input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    /// The offending text, usually a single token or line.
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    /// Creates an error positioned at the start of the input.
+    pub fn new(day: u8, reason: impl Into<String>) -> Self {
+        Self {
+            day,
+            line: 1,
+            column: 1,
+            text: String::new(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Positions the error at `token`, a slice of `line`, which is the
+    /// `index`th (0-based) line of the input.
+    ///
+    /// If `token` does not point into `line` the error is placed at the
+    /// first occurrence of its text, falling back to the start of the line.
+    pub fn at(mut self, index: usize, line: &str, token: &str) -> Self {
+        self.line = index + 1;
+        self.column = column_of(line, token);
+        self.text = token.to_string();
+        self
+    }
+
+    /// Re-anchors an error raised while parsing `token` on its own.
+    ///
+    /// Parsers of a single token, such as [`Point::parse`] and
+    /// [`Interval::parse`], position their errors as if the token were the
+    /// first line of the input; this places them in the full input instead.
+    ///
+    /// `token` is a slice of `line`, the `index`th (0-based) line of the
+    /// input. Positions on the first line of `token` are shifted along by
+    /// the column `token` starts at, and every line is shifted down by `index`.
+    ///
+    /// [`Point::parse`]: crate::point::Point::parse
+    /// [`Interval::parse`]: crate::interval::Interval::parse
+    pub fn within(mut self, index: usize, line: &str, token: &str) -> Self {
+        if self.line == 1 {
+            self.column += column_of(line, token) - 1;
+        }
+        self.line += index;
+        self
+    }
+}
+
+/// The 1-based character column at which `token` starts in `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    let within_line =
+        token_start >= line_start && token_start + token.len() <= line_start + line.len();
+    let offset = if within_line {
+        token_start - line_start
+    } else {
+        line.find(token).unwrap_or(0)
+    };
+    line[..offset].chars().count() + 1
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.reason
+        )?;
+        if !self.text.is_empty() {
+            write!(f, " (found `{}`)", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_error_at_token() {
+        let line = "move x from 1 to 2";
+        let error = ParseError::new(5, "expected a number").at(3, line, &line[5..6]);
+        assert_eq!((error.line, error.column), (4, 6));
+        assert_eq!(error.text, "x");
+        assert_eq!(
+            error.to_string(),
+            "day 5, line 4, column 6: expected a number (found `x`)"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_searching_for_copied_token() {
+        let error = ParseError::new(9, "unknown direction").at(0, "Q 4", "Q");
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn reanchors_error_inside_token() {
+        let line = "498,4 -> 498,x";
+        let token = &line[9..];
+        let error = ParseError::new(14, "expected a number")
+            .at(0, token, &token[4..])
+            .within(2, line, token);
+        assert_eq!((error.line, error.column), (3, 14));
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::point::Point;
+use crate::ParseError;
+
+/// A dense, rectangular grid of cells stored in row-major order.
+///
+/// `x` increases along a row and `y` increases down the grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a `width` by `height` grid, filling each cell from its position.
+    pub fn from_fn(width: usize, height: usize, cell: impl FnMut(Point<usize>) -> T) -> Self {
+        let cells = (0..width * height)
+            .map(|index| Point::new(index % width, index / width))
+            .map(cell)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// A grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Parses a map with one character per cell and one row per line.
+    ///
+    /// `cell` maps the character at each position to a cell, or returns
+    /// why the character is invalid. Every row must be the same length.
+    pub fn parse<F>(day: u8, input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(Point<usize>, char) -> Result<T, &'static str>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (y, line) in input.lines().enumerate() {
+            let error = |reason: &str, token: &str| ParseError::new(day, reason).at(y, line, token);
+            let mut row_width = 0;
+            for (x, (position, ch)) in line.char_indices().enumerate() {
+                let token = &line[position..position + ch.len_utf8()];
+                cells.push(cell(Point::new(x, y), ch).map_err(|reason| error(reason, token))?);
+                row_width += 1;
+            }
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(error(
+                        &format!("Expected a row of {width} cells, found {row_width}"),
+                        line,
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Point<usize>) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    pub fn get(&self, position: Point<usize>) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Point<usize>) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    fn index_of(&self, position: Point<usize>) -> Option<usize> {
+        self.contains(position)
+            .then_some(position.y * self.width + position.x)
+    }
+
+    /// Every position in the grid, in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Point<usize>> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |index| Point::new(index % width, index / width))
+    }
+
+    /// Every cell in the grid alongside its position, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.ray(Point::new(0, y), Point::new(1, 0))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.ray(Point::new(x, 0), Point::new(0, 1))
+    }
+
+    /// Cells along the diagonal running down and to the right from `start`.
+    pub fn diagonal(&self, start: Point<usize>) -> impl Iterator<Item = &T> {
+        self.ray(start, Point::new(1, 1))
+    }
+
+    /// Cells along the diagonal running down and to the left from `start`.
+    pub fn anti_diagonal(&self, start: Point<usize>) -> impl Iterator<Item = &T> {
+        self.ray(start, Point::new(-1, 1))
+    }
+
+    /// Cells from `start` (inclusive) stepping by `step` until the edge.
+    pub fn ray(&self, start: Point<usize>, step: Point<isize>) -> impl Iterator<Item = &T> {
+        std::iter::successors(self.contains(start).then_some(start), move |&position| {
+            self.offset(position, step)
+        })
+        .map(|position| &self[position])
+    }
+
+    /// The position `step` away from `position`, if it is inside the grid.
+    pub fn offset(&self, position: Point<usize>, step: Point<isize>) -> Option<Point<usize>> {
+        let position = Point::new(
+            position.x.checked_add_signed(step.x)?,
+            position.y.checked_add_signed(step.y)?,
+        );
+        self.contains(position).then_some(position)
+    }
+
+    /// The orthogonal neighbours of `position` that are inside the grid.
+    pub fn neighbours(&self, position: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        position
+            .neighbours()
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// The orthogonal and diagonal neighbours of `position` that are inside the grid.
+    pub fn neighbours_8(&self, position: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        position
+            .neighbours_8()
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Point<usize>) -> &Self::Output {
+        self.get(position)
+            .unwrap_or_else(|| panic!("Position {position:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, position: Point<usize>) -> &mut Self::Output {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("Position {position:?} is outside the grid"))
+    }
+}
+
+/// Renders each cell in turn, with one line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(0, input, |_, ch| ch.to_digit(10).ok_or("not a digit")).unwrap()
+    }
+
+    #[test]
+    fn parses_and_indexes_cells() {
+        let grid = digits("123\n456");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+    }
+
+    #[test]
+    fn rejects_ragged_rows_and_invalid_cells() {
+        let error = Grid::parse(8, "123\n45", |_, ch| ch.to_digit(10).ok_or("not a digit"));
+        assert_eq!(error.unwrap_err().line, 2);
+        let error = Grid::parse(8, "123\n4x6", |_, ch| ch.to_digit(10).ok_or("not a digit"));
+        assert_eq!(error.unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn iterates_lines_through_the_grid() {
+        let grid = digits("123\n456\n789");
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), [4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6, 9]);
+        assert_eq!(
+            grid.diagonal(Point::new(0, 0)).copied().collect::<Vec<_>>(),
+            [1, 5, 9]
+        );
+        assert_eq!(
+            grid.anti_diagonal(Point::new(2, 0))
+                .copied()
+                .collect::<Vec<_>>(),
+            [3, 5, 7]
+        );
+        assert_eq!(
+            grid.ray(Point::new(1, 1), Point::new(0, -1))
+                .copied()
+                .collect::<Vec<_>>(),
+            [5, 2]
+        );
+    }
+
+    #[test]
+    fn neighbours_stay_inside_the_grid() {
+        let grid = digits("123\n456\n789");
+        assert_eq!(grid.neighbours(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours_8(Point::new(1, 1)).count(), 8);
+    }
+}
@@ -0,0 +1,312 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::point::Scalar;
+
+/// A non-empty range of values including both its ends, such as the
+/// sections `2-4`, which are 2, 3 and 4.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Scalar> Interval<T> {
+    /// The values from `start` to `end` inclusive, or `None` if `start`
+    /// comes after `end`.
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// The interval holding only `value`.
+    pub fn single(value: T) -> Self {
+        Self {
+            start: value,
+            end: value,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Number of values in the interval, widened so an interval spanning
+    /// every value of `T` can still be counted.
+    pub fn len(&self) -> u128 {
+        (self.end.widen() - self.start.widen()) as u128 + 1
+    }
+
+    /// Always false, as an interval holds at least its start.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether every value of `other` is also in `self`.
+    pub fn covers(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Whether the intervals share at least one value.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Whether the intervals overlap or one ends right before the other starts.
+    pub fn touches(&self, other: &Self) -> bool {
+        let before = |a: &Self, b: &Self| a.end.checked_add(T::ONE) == Some(b.start);
+        self.overlaps(other) || before(self, other) || before(other, self)
+    }
+
+    /// The values in both intervals.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The values in either interval, if together they are contiguous.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other).then(|| Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    /// The values in `self` but not `other`: the parts before and after
+    /// `other`, either of which may be missing.
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        if !self.overlaps(other) {
+            return match other.start > self.end {
+                true => (Some(*self), None),
+                false => (None, Some(*self)),
+            };
+        }
+        let before = other
+            .start
+            .checked_sub(T::ONE)
+            .and_then(|end| Self::new(self.start, end))
+            .filter(|_| self.start < other.start);
+        let after = other
+            .end
+            .checked_add(T::ONE)
+            .and_then(|start| Self::new(start, self.end))
+            .filter(|_| other.end < self.end);
+        (before, after)
+    }
+}
+
+impl<T: Scalar + FromStr> Interval<T> {
+    /// Parses an interval written as `a-b`.
+    ///
+    /// See [`ParseError::within`] for placing errors in the full input.
+    pub fn parse(day: u8, s: &str) -> Result<Self, ParseError> {
+        let error = |reason: &str, token: &str| ParseError::new(day, reason).at(0, s, token);
+        let value = |token: &str| {
+            token
+                .trim()
+                .parse::<T>()
+                .map_err(|_| error("Expected an integer bound", token))
+        };
+        // Skip the first character, so a negative start is not taken as
+        // the separator
+        let separator = s
+            .char_indices()
+            .skip(1)
+            .find(|&(_, ch)| ch == '-')
+            .map(|(position, _)| position);
+        match separator {
+            Some(position) => {
+                let (start, end) = (&s[..position], &s[position + 1..]);
+                Self::new(value(start)?, value(end)?)
+                    .ok_or_else(|| error("Interval starts after it ends", s))
+            }
+            None => Err(error("Malformed input, expected `a-b`", s)),
+        }
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, disjoint intervals, with no two
+/// intervals touching.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    runs: Vec<Interval<T>>,
+}
+
+impl<T: Scalar> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { runs: vec![] }
+    }
+
+    /// Adds every value of `interval`, merging it with any runs it touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // Runs entirely before or after `interval` are kept as they are
+        let first = self
+            .runs
+            .partition_point(|run| run.end < interval.start && !run.touches(&interval));
+        let last = self
+            .runs
+            .partition_point(|run| run.start <= interval.end || run.touches(&interval));
+        let merged = self.runs[first..last]
+            .iter()
+            .fold(interval, |merged, run| merged.union(run).unwrap());
+        self.runs.splice(first..last, [merged]);
+    }
+
+    /// The disjoint runs of values, in ascending order.
+    pub fn runs(&self) -> &[Interval<T>] {
+        &self.runs
+    }
+
+    /// Number of values in the set, widened as for [`Interval::len`].
+    pub fn len(&self) -> u128 {
+        self.runs.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.covers(&Interval::single(value))
+    }
+
+    /// Whether every value of `interval` is in the set.
+    pub fn covers(&self, interval: &Interval<T>) -> bool {
+        let index = self.runs.partition_point(|run| run.end < interval.start);
+        self.runs.get(index).is_some_and(|run| run.covers(interval))
+    }
+
+    /// The smallest interval holding every value in the set.
+    pub fn span(&self) -> Option<Interval<T>> {
+        Some(Interval {
+            start: self.runs.first()?.start,
+            end: self.runs.last()?.end,
+        })
+    }
+
+    /// The values missing between the first and last runs.
+    pub fn gaps(&self) -> Vec<Interval<T>> {
+        self.runs
+            .windows(2)
+            .map(|pair| Interval {
+                start: pair[0].end + T::ONE,
+                end: pair[1].start - T::ONE,
+            })
+            .collect()
+    }
+}
+
+impl<T: Scalar> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        let mut set = Self::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: i32, end: i32) -> Interval<i32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn compares_inclusive_ends() {
+        let (a, b) = (interval(2, 4), interval(4, 8));
+        assert!(a.overlaps(&b) && b.overlaps(&a));
+        assert_eq!(a.intersection(&b), Some(Interval::single(4)));
+        assert!(!interval(2, 3).overlaps(&b));
+        assert!(interval(2, 8).covers(&a) && !a.covers(&b));
+        assert!(a.contains(4) && !a.contains(5));
+        assert_eq!(a.len(), 3);
+        assert_eq!(Interval::new(5, 4), None);
+    }
+
+    #[test]
+    fn combines_intervals() {
+        assert_eq!(interval(2, 3).union(&interval(4, 6)), Some(interval(2, 6)));
+        assert_eq!(interval(2, 3).union(&interval(5, 6)), None);
+        assert_eq!(
+            interval(1, 9).difference(&interval(3, 4)),
+            (Some(interval(1, 2)), Some(interval(5, 9)))
+        );
+        assert_eq!(
+            interval(1, 9).difference(&interval(1, 4)),
+            (None, Some(interval(5, 9)))
+        );
+        assert_eq!(interval(3, 4).difference(&interval(1, 9)), (None, None));
+        assert_eq!(
+            interval(1, 2).difference(&interval(5, 9)),
+            (Some(interval(1, 2)), None)
+        );
+        let full = Interval::new(0u8, u8::MAX).unwrap();
+        assert_eq!(full.len(), 256);
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).unwrap().len(), 1 << 64);
+        assert_eq!(
+            full.difference(&Interval::single(u8::MAX))
+                .0
+                .map(|i| i.end()),
+            Some(254)
+        );
+    }
+
+    #[test]
+    fn parses_intervals() {
+        assert_eq!(
+            Interval::parse(4, "2-4"),
+            Ok(Interval::new(2u16, 4).unwrap())
+        );
+        assert_eq!(Interval::parse(4, "-3--1"), Ok(interval(-3, -1)));
+        assert_eq!(interval(-3, -1).to_string(), "-3--1");
+        let error = Interval::<u16>::parse(4, "6-x").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "x"));
+        let error = Interval::<u16>::parse(4, "6-2").unwrap_err();
+        assert_eq!(error.reason, "Interval starts after it ends");
+        assert!(Interval::<u16>::parse(4, "6").is_err());
+    }
+
+    #[test]
+    fn merges_touching_intervals() {
+        let mut set = [interval(10, 12), interval(1, 2), interval(5, 6)]
+            .into_iter()
+            .collect::<IntervalSet<_>>();
+        assert_eq!(
+            set.runs(),
+            [interval(1, 2), interval(5, 6), interval(10, 12)]
+        );
+        assert_eq!(set.gaps(), [interval(3, 4), interval(7, 9)]);
+        set.insert(interval(3, 3));
+        set.insert(interval(7, 10));
+        assert_eq!(set.runs(), [interval(1, 3), interval(5, 12)]);
+        assert_eq!(set.len(), 11);
+        assert_eq!(set.span(), Some(interval(1, 12)));
+        assert!(set.covers(&interval(6, 11)) && !set.covers(&interval(3, 5)));
+        assert!(set.contains(12) && !set.contains(4));
+        set.insert(interval(0, 20));
+        assert_eq!(set.runs(), [interval(0, 20)]);
+        let set = [
+            Interval::new(0u8, 9).unwrap(),
+            Interval::new(20, u8::MAX).unwrap(),
+        ]
+        .into_iter()
+        .collect::<IntervalSet<_>>();
+        assert_eq!(set.len(), 246);
+    }
+}
@@ -1,30 +1,30 @@
 #![feature(iter_array_chunks)]
 
-#[allow(dead_code)]
-mod day_1;
-#[allow(dead_code)]
-mod day_10;
-#[allow(dead_code)]
-mod day_11;
-#[allow(dead_code)]
-mod day_12;
-#[allow(dead_code)]
-mod day_13;
-#[allow(dead_code)]
-mod day_14;
-#[allow(dead_code)]
-mod day_2;
-#[allow(dead_code)]
-mod day_3;
-#[allow(dead_code)]
-mod day_4;
-#[allow(dead_code)]
-mod day_5;
-#[allow(dead_code)]
-mod day_6;
-#[allow(dead_code)]
-mod day_7;
-#[allow(dead_code)]
-mod day_8;
-#[allow(dead_code)]
-mod day_9;
+pub mod bench;
+pub mod day_1;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod error;
+pub mod grid;
+pub mod interval;
+pub mod point;
+pub mod render;
+pub mod report;
+pub mod search;
+pub mod simplex;
+pub mod solution;
+pub mod verify;
+
+pub use error::ParseError;
+pub use solution::{input_path, registry, Answer, Part, Run, Solution, Solver, Timing};
@@ -0,0 +1,264 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+use crate::ParseError;
+
+/// An integer type that can be used as a coordinate of a [`Point`].
+pub trait Scalar: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// The value as an `i128`, which holds every value of every scalar.
+    fn widen(self) -> i128;
+}
+
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn widen(self) -> i128 {
+                self as i128
+            }
+        })*
+    };
+}
+
+scalar!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Unit steps to the four orthogonal neighbours of a point.
+const ORTHOGONAL_STEPS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Unit steps to all eight neighbours of a point, orthogonal and diagonal.
+const ALL_AROUND_STEPS: [(i8, i8); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// A point, or a vector between two points, on an integer grid.
+///
+/// Points order row-major, by `y` then `x`, so sorted points read like
+/// text. Use [`Point::row_major`] or [`Point::column_major`] as a sort key
+/// to make the order explicit.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Point<isize> {
+    /// Unit vectors pointing right, left, down and up.
+    pub const ORTHOGONAL: [Self; 4] = [
+        Self::new(1, 0),
+        Self::new(-1, 0),
+        Self::new(0, 1),
+        Self::new(0, -1),
+    ];
+}
+
+impl<T: Scalar> Point<T> {
+    /// Key ordering points by `y`, then by `x`.
+    pub fn row_major(&self) -> (T, T) {
+        (self.y, self.x)
+    }
+
+    /// Key ordering points by `x`, then by `y`.
+    pub fn column_major(&self) -> (T, T) {
+        (self.x, self.y)
+    }
+
+    /// The taxicab distance between two points.
+    pub fn manhattan(&self, other: &Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+
+    /// The number of king's moves between two points.
+    pub fn chebyshev(&self, other: &Self) -> T {
+        distance(self.x, other.x).max(distance(self.y, other.y))
+    }
+
+    /// Moves at most one unit along each axis towards `target`.
+    pub fn step_towards(&self, target: &Self) -> Self {
+        Self::new(towards(self.x, target.x), towards(self.y, target.y))
+    }
+
+    /// The orthogonal neighbours that can be represented in `T`.
+    pub fn neighbours(&self) -> impl Iterator<Item = Self> {
+        let point = *self;
+        ORTHOGONAL_STEPS
+            .into_iter()
+            .filter_map(move |step| point.nudge(step))
+    }
+
+    /// The orthogonal and diagonal neighbours that can be represented in `T`.
+    pub fn neighbours_8(&self) -> impl Iterator<Item = Self> {
+        let point = *self;
+        ALL_AROUND_STEPS
+            .into_iter()
+            .filter_map(move |step| point.nudge(step))
+    }
+
+    fn nudge(&self, (dx, dy): (i8, i8)) -> Option<Self> {
+        let nudge = |value: T, delta: i8| match delta.signum() {
+            -1 => value.checked_sub(T::ONE),
+            1 => value.checked_add(T::ONE),
+            _ => Some(value),
+        };
+        Some(Self::new(nudge(self.x, dx)?, nudge(self.y, dy)?))
+    }
+}
+
+impl<T: Scalar + FromStr> Point<T> {
+    /// Parses a point written as `x,y`.
+    ///
+    /// See [`ParseError::within`] for placing errors in the full input.
+    pub fn parse(day: u8, s: &str) -> Result<Self, ParseError> {
+        let error = |reason: &str, token: &str| ParseError::new(day, reason).at(0, s, token);
+        let value = |token: &str| {
+            token
+                .trim()
+                .parse::<T>()
+                .map_err(|_| error("Expected an integer coordinate", token))
+        };
+        match s.split_once(',') {
+            Some((x, y)) => Ok(Self::new(value(x)?, value(y)?)),
+            _ => Err(error("Malformed input, expected `x,y`", s)),
+        }
+    }
+}
+
+fn distance<T: Scalar>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn towards<T: Scalar>(from: T, to: T) -> T {
+    match from.cmp(&to) {
+        Ordering::Less => from + T::ONE,
+        Ordering::Greater => from - T::ONE,
+        Ordering::Equal => from,
+    }
+}
+
+impl<T: Scalar> Ord for Point<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.row_major().cmp(&other.row_major())
+    }
+}
+
+impl<T: Scalar> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_distances() {
+        let (a, b) = (Point::new(1i16, -2), Point::new(-3, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a - b, Point::new(4, -3));
+        assert_eq!(-(a + b), Point::new(2, 1));
+    }
+
+    #[test]
+    fn steps_towards_target() {
+        let point = Point::new(5usize, 5);
+        assert_eq!(point.step_towards(&Point::new(9, 5)), Point::new(6, 5));
+        assert_eq!(point.step_towards(&Point::new(0, 0)), Point::new(4, 4));
+        assert_eq!(point.step_towards(&point), point);
+    }
+
+    #[test]
+    fn skips_unrepresentable_neighbours() {
+        let origin = Point::new(0usize, 0);
+        assert_eq!(origin.neighbours().count(), 2);
+        assert_eq!(origin.neighbours_8().count(), 3);
+        assert_eq!(Point::new(0i8, 0).neighbours_8().count(), 8);
+    }
+
+    #[test]
+    fn orders_row_major() {
+        let mut points = vec![Point::new(1, 0), Point::new(0, 1), Point::new(0, 0)];
+        points.sort();
+        assert_eq!(
+            points,
+            [Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)]
+        );
+        points.sort_by_key(Point::column_major);
+        assert_eq!(
+            points,
+            [Point::new(0, 0), Point::new(0, 1), Point::new(1, 0)]
+        );
+    }
+
+    #[test]
+    fn parses_x_comma_y() {
+        assert_eq!(Point::<usize>::parse(14, "498,4"), Ok(Point::new(498, 4)));
+        let error = Point::<usize>::parse(14, "498,x").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (5, "x"));
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
+
+use crate::grid::Grid;
+
+/// A colour with 8-bit red, green and blue channels.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// The colour `numerator / denominator` of the way from `self` to `other`.
+    pub fn blend(self, other: Self, numerator: u32, denominator: u32) -> Self {
+        let channel = |from: u8, to: u8| {
+            let (from, to) = (from as u32, to as u32);
+            ((from * (denominator - numerator) + to * numerator) / denominator.max(1)) as u8
+        };
+        Self::new(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+        )
+    }
+
+    /// Perceived brightness, using the Rec. 601 weights.
+    pub fn luma(&self) -> u8 {
+        ((299 * self.r as u32 + 587 * self.g as u32 + 114 * self.b as u32) / 1000) as u8
+    }
+}
+
+/// One cell of a frame: a glyph for text output and a colour for
+/// terminals and images.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub colour: Rgb,
+}
+
+impl Cell {
+    pub const fn new(glyph: char, colour: Rgb) -> Self {
+        Self { glyph, colour }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ', Rgb::BLACK)
+    }
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(self.glyph)
+    }
+}
+
+/// A picture of a solver's state, ready to be written by a [`Backend`].
+pub type Frame = Grid<Cell>;
+
+/// State that can be drawn as a [`Frame`].
+pub trait Render {
+    fn render(&self) -> Frame;
+}
+
+/// An output format for frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// The glyph of each cell, one line per row.
+    Text,
+    /// Glyphs coloured with 24-bit ANSI escape codes.
+    Ansi,
+    /// A binary colour PPM image, with each cell drawn as a square of pixels.
+    Ppm,
+    /// A binary greyscale PGM image, with each cell drawn as a square of pixels.
+    Pgm,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "text" => Ok(Backend::Text),
+            "ansi" => Ok(Backend::Ansi),
+            "ppm" => Ok(Backend::Ppm),
+            "pgm" => Ok(Backend::Pgm),
+            other => Err(format!(
+                "Unknown backend `{other}`, expected `text`, `ansi`, `ppm` or `pgm`"
+            )),
+        }
+    }
+}
+
+impl Backend {
+    /// Encodes `frame`, drawing each cell `scale` pixels wide in images.
+    pub fn encode(&self, frame: &Frame, scale: usize) -> Vec<u8> {
+        match self {
+            Backend::Text => text(frame).into_bytes(),
+            Backend::Ansi => ansi(frame).into_bytes(),
+            Backend::Ppm => ppm(frame, scale),
+            Backend::Pgm => pgm(frame, scale),
+        }
+    }
+}
+
+pub fn text(frame: &Frame) -> String {
+    frame.to_string()
+}
+
+pub fn ansi(frame: &Frame) -> String {
+    let mut output = String::new();
+    for (y, row) in frame.rows().enumerate() {
+        if y > 0 {
+            output.push('\n');
+        }
+        let mut current = None;
+        for cell in row {
+            if current != Some(cell.colour) {
+                let Rgb { r, g, b } = cell.colour;
+                write!(output, "\x1b[38;2;{r};{g};{b}m").unwrap();
+                current = Some(cell.colour);
+            }
+            output.push(cell.glyph);
+        }
+        output.push_str("\x1b[0m");
+    }
+    output
+}
+
+pub fn ppm(frame: &Frame, scale: usize) -> Vec<u8> {
+    image(frame, scale, "P6", |colour| {
+        vec![colour.r, colour.g, colour.b]
+    })
+}
+
+pub fn pgm(frame: &Frame, scale: usize) -> Vec<u8> {
+    image(frame, scale, "P5", |colour| vec![colour.luma()])
+}
+
+/// A binary Netpbm image with the given magic number and pixel encoding.
+fn image(frame: &Frame, scale: usize, magic: &str, pixel: impl Fn(Rgb) -> Vec<u8>) -> Vec<u8> {
+    let scale = scale.max(1);
+    let mut image = format!(
+        "{magic}\n{} {}\n255\n",
+        frame.width() * scale,
+        frame.height() * scale
+    )
+    .into_bytes();
+    for row in frame.rows() {
+        let line = row
+            .iter()
+            .flat_map(|cell| pixel(cell.colour).repeat(scale))
+            .collect::<Vec<_>>();
+        for _ in 0..scale {
+            image.extend_from_slice(&line);
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    fn frame() -> Frame {
+        let red = Cell::new('#', Rgb::new(255, 0, 0));
+        let mut frame = Grid::new(2, 1, Cell::new('.', Rgb::WHITE));
+        frame[Point::new(0, 0)] = red;
+        frame
+    }
+
+    #[test]
+    fn writes_text_and_ansi() {
+        assert_eq!(text(&frame()), "#.");
+        assert_eq!(
+            ansi(&frame()),
+            "\x1b[38;2;255;0;0m#\x1b[38;2;255;255;255m.\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn writes_scaled_images() {
+        let image = ppm(&frame(), 2);
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 4 * 2 * 3);
+        assert_eq!(&image[header.len()..][..6], [255, 0, 0, 255, 0, 0]);
+
+        let image = pgm(&frame(), 1);
+        assert_eq!(image, b"P5\n2 1\n255\n\x4c\xff");
+    }
+
+    #[test]
+    fn blends_colours() {
+        let grey = Rgb::BLACK.blend(Rgb::WHITE, 1, 2);
+        assert_eq!(grey, Rgb::new(127, 127, 127));
+        assert_eq!(Rgb::BLACK.blend(Rgb::WHITE, 0, 2), Rgb::BLACK);
+    }
+}
@@ -0,0 +1,225 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::{input_path, registry, Answer, Part, Solver};
+
+/// Column names for the rows written by [`Record::to_csv`].
+pub const CSV_HEADER: &str = "day,part,input,answer,answer_type,parse_ns,solve_ns,success,error\n";
+
+/// A machine-readable encoding for a set of [`Record`]s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One JSON object per line.
+    JsonLines,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "json" | "jsonl" => Ok(Format::JsonLines),
+            "csv" => Ok(Format::Csv),
+            other => Err(format!(
+                "Unknown format `{other}`, expected `json` or `csv`"
+            )),
+        }
+    }
+}
+
+/// The result of solving one part of a day on one input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    /// Name of the input, e.g. a bundled input such as `test`, or a path.
+    pub input: String,
+    /// The answer produced, or why no answer could be produced.
+    pub outcome: Result<Answer, String>,
+    /// Time spent parsing, whether or not it succeeded. Zero if the solver
+    /// was never reached.
+    pub parse: Duration,
+    /// Time spent solving, which is zero if the run failed.
+    pub solve: Duration,
+}
+
+impl Record {
+    /// Solves `part` of `solver` on `input`, recording the answer or error.
+    pub fn new(solver: &dyn Solver, part: Part, input_name: &str, input: &str) -> Self {
+        let start = Instant::now();
+        let run = solver.run(input, part);
+        // A run only fails while parsing, so that is where its time went
+        let failed = start.elapsed();
+        Self {
+            day: solver.day(),
+            part,
+            input: input_name.to_string(),
+            parse: run.as_ref().map_or(failed, |run| run.parse),
+            solve: run.as_ref().map(|run| run.solve).unwrap_or_default(),
+            outcome: run.map(|run| run.answer).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// A record for a run that failed before the solver was reached.
+    pub fn failed(day: u8, part: Part, input_name: &str, error: String) -> Self {
+        Self {
+            day,
+            part,
+            input: input_name.to_string(),
+            outcome: Err(error),
+            parse: Duration::ZERO,
+            solve: Duration::ZERO,
+        }
+    }
+
+    /// The record as a single-line JSON object.
+    ///
+    /// Numeric answers are written as JSON numbers and text answers as
+    /// strings. `answer` and `answer_type` are `null` if the run failed,
+    /// and `error` is `null` if it succeeded.
+    pub fn to_json(&self) -> String {
+        let (answer, answer_type, error) = match &self.outcome {
+            Ok(Answer::Text(text)) => (json_string(text), json_string("text"), "null".into()),
+            Ok(answer) => (
+                answer.to_string(),
+                json_string(answer.kind()),
+                "null".into(),
+            ),
+            Err(e) => ("null".into(), "null".into(), json_string(e)),
+        };
+        format!(
+            "{{\"day\":{},\"part\":{},\"input\":{},\"answer\":{answer},\"answer_type\":{answer_type},\"parse_ns\":{},\"solve_ns\":{},\"success\":{},\"error\":{error}}}\n",
+            self.day,
+            self.part,
+            json_string(&self.input),
+            self.parse.as_nanos(),
+            self.solve.as_nanos(),
+            self.outcome.is_ok(),
+        )
+    }
+
+    /// The record as a CSV row, with columns matching [`CSV_HEADER`].
+    pub fn to_csv(&self) -> String {
+        let (answer, answer_type, error) = match &self.outcome {
+            Ok(answer) => (answer.to_string(), answer.kind(), String::new()),
+            Err(e) => (String::new(), "", e.clone()),
+        };
+        format!(
+            "{},{},{},{},{answer_type},{},{},{},{}\n",
+            self.day,
+            self.part,
+            csv_field(&self.input),
+            csv_field(&answer),
+            self.parse.as_nanos(),
+            self.solve.as_nanos(),
+            self.outcome.is_ok(),
+            csv_field(&error),
+        )
+    }
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch.is_control() => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Quotes `text` if it contains a comma, quote or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Encodes `records` in `format`, including the header row for CSV.
+pub fn write(records: &[Record], format: Format) -> String {
+    match format {
+        Format::JsonLines => records.iter().map(Record::to_json).collect(),
+        Format::Csv => records
+            .iter()
+            .fold(CSV_HEADER.to_string(), |csv, record| csv + &record.to_csv()),
+    }
+}
+
+/// Solves both parts of every registered day, or a single day, on its
+/// bundled input named `input_name`.
+pub fn report_all(day: Option<u8>, input_name: &str) -> Vec<Record> {
+    registry()
+        .into_iter()
+        .filter(|(number, _)| day.is_none_or(|day| day == *number))
+        .flat_map(|(number, solver)| {
+            let path = input_path(number, input_name);
+            let input = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read `{}`: {e}", path.display()));
+            [Part::One, Part::Two].map(|part| match &input {
+                Ok(input) => Record::new(solver, part, input_name, input),
+                Err(e) => Record::failed(number, part, input_name, e.clone()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_4::Day4;
+    use crate::day_6::Day6;
+
+    fn record(outcome: Result<Answer, String>) -> Record {
+        Record {
+            day: 10,
+            part: Part::Two,
+            input: "test".to_string(),
+            outcome,
+            parse: Duration::from_nanos(5),
+            solve: Duration::from_nanos(7),
+        }
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let json = record(Ok(Answer::Text("#\"\n.".into()))).to_json();
+        assert_eq!(
+            json,
+            "{\"day\":10,\"part\":2,\"input\":\"test\",\"answer\":\"#\\\"\\n.\",\"answer_type\":\"text\",\"parse_ns\":5,\"solve_ns\":7,\"success\":true,\"error\":null}\n"
+        );
+        let json = record(Ok(Answer::Signed(-3))).to_json();
+        assert!(json.contains("\"answer\":-3,\"answer_type\":\"signed\""));
+    }
+
+    #[test]
+    fn writes_csv_rows() {
+        let csv = record(Err("bad, \"very\" bad".into())).to_csv();
+        assert_eq!(csv, "10,2,test,,,5,7,false,\"bad, \"\"very\"\" bad\"\n");
+        let csv = write(&[record(Ok(Answer::Unsigned(8)))], Format::Csv);
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.ends_with("10,2,test,8,unsigned,5,7,true,\n"));
+    }
+
+    #[test]
+    fn records_parse_errors() {
+        let record = Record::new(&Day4, Part::One, "stdin", "1-2,x");
+        assert!(record.outcome.unwrap_err().starts_with("day 4, line 1"));
+        assert!(record.parse > Duration::ZERO);
+        assert_eq!(record.solve, Duration::ZERO);
+        let record = Record::new(&Day6, Part::One, "test1", include_str!("day_6/test1"));
+        assert_eq!(record.outcome, Ok(Answer::Unsigned(7)));
+    }
+}
@@ -0,0 +1,310 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::point::Scalar;
+
+/// Counters describing how much work a search did.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Nodes whose neighbours were explored.
+    pub expanded: usize,
+    /// Distinct nodes reached, including those never expanded.
+    pub discovered: usize,
+    /// Largest number of entries waiting on the frontier at once.
+    pub max_frontier: usize,
+}
+
+/// The outcome of a search: the distance to every settled node and the
+/// goal it stopped at, if any.
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    distances: HashMap<N, C>,
+    parents: HashMap<N, N>,
+    goal: Option<N>,
+    pub stats: Stats,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new() -> Self {
+        Self {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+            goal: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// The first goal node reached, if the search found one.
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// The cost of the cheapest path to the goal.
+    pub fn cost(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    /// The cost of the cheapest path to `node`, if it was settled.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// The cost of the cheapest path to every settled node.
+    ///
+    /// When the search stops early at a goal, nodes that were not yet
+    /// settled are left out.
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    /// The cheapest path to the goal, from the start it was reached from.
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+
+    /// The cheapest path to `node`, from the start it was reached from.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search, where every step costs one.
+///
+/// Searches outwards from every node in `starts` at once, and stops at the
+/// first node for which `is_goal` holds. Pass `|_| false` to settle every
+/// reachable node.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+    search.stats.discovered = queue.len();
+
+    while let Some(node) = queue.pop_front() {
+        search.stats.max_frontier = search.stats.max_frontier.max(queue.len() + 1);
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        search.stats.expanded += 1;
+        let distance = search.distances[&node];
+        for next in neighbours(&node) {
+            if !search.distances.contains_key(&next) {
+                search.stats.discovered += 1;
+                search.distances.insert(next.clone(), distance + 1);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm, where `cost` gives the cost of each step.
+///
+/// Costs must not be negative. See [`bfs`] for `starts` and `is_goal`.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    cost: impl FnMut(&N, &N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Scalar,
+    I: IntoIterator<Item = N>,
+{
+    astar(starts, neighbours, cost, |_| C::ZERO, is_goal)
+}
+
+/// A node waiting on the frontier, ordered so the heap pops the lowest
+/// estimated total cost first.
+struct Frontier<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Frontier<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N, C: Ord> Eq for Frontier<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Frontier<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Frontier<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Reverse(&self.estimate).cmp(&Reverse(&other.estimate))
+    }
+}
+
+/// A* search, guided by a `heuristic` estimate of the cost to a goal.
+///
+/// The heuristic must never overestimate and must be consistent, or the
+/// costs found may not be the cheapest. See [`bfs`] for `starts` and
+/// `is_goal`.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut cost: impl FnMut(&N, &N) -> C,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Scalar,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    // Cheapest cost found so far to each node, and the node it was reached from
+    let mut best: HashMap<N, (C, Option<N>)> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if !best.contains_key(&start) {
+            best.insert(start.clone(), (C::ZERO, None));
+            heap.push(Frontier {
+                estimate: heuristic(&start),
+                cost: C::ZERO,
+                node: start,
+            });
+        }
+    }
+
+    while let Some(Frontier {
+        cost: spent, node, ..
+    }) = heap.pop()
+    {
+        search.stats.max_frontier = search.stats.max_frontier.max(heap.len() + 1);
+        // Skip stale entries superseded by a cheaper path
+        if search.distances.contains_key(&node) || best[&node].0 < spent {
+            continue;
+        }
+        search.distances.insert(node.clone(), spent);
+        if let Some(parent) = &best[&node].1 {
+            search.parents.insert(node.clone(), parent.clone());
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        search.stats.expanded += 1;
+        for next in neighbours(&node) {
+            if search.distances.contains_key(&next) {
+                continue;
+            }
+            let total = spent + cost(&node, &next);
+            if best.get(&next).is_none_or(|&(known, _)| total < known) {
+                best.insert(next.clone(), (total, Some(node.clone())));
+                heap.push(Frontier {
+                    estimate: total + heuristic(&next),
+                    cost: total,
+                    node: next,
+                });
+            }
+        }
+    }
+    search.stats.discovered = best.len();
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    /// Steps between neighbouring points on an unbounded grid, skipping walls.
+    fn open(walls: &[Point<i32>]) -> impl FnMut(&Point<i32>) -> Vec<Point<i32>> + '_ {
+        move |point| {
+            point
+                .neighbours()
+                .filter(|next| !walls.contains(next))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path_around_walls() {
+        let walls = [Point::new(1, -1), Point::new(1, 0), Point::new(1, 1)];
+        let goal = Point::new(2, 0);
+        let search = bfs([Point::new(0, 0)], open(&walls), |&p| p == goal);
+        assert_eq!(search.cost(), Some(6));
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!((path[0], path[6]), (Point::new(0, 0), goal));
+        assert!(path.windows(2).all(|step| step[0].manhattan(&step[1]) == 1));
+    }
+
+    #[test]
+    fn searches_from_every_start() {
+        let graph = |&n: &u8| match n {
+            0 => vec![1],
+            1 => vec![2],
+            5 => vec![2],
+            _ => vec![],
+        };
+        let search = bfs([0, 5], graph, |_| false);
+        assert_eq!(search.distance(&2), Some(1));
+        assert_eq!(search.path_to(&2), Some(vec![5, 2]));
+        assert_eq!(search.distances().len(), 4);
+        assert_eq!(search.goal(), None);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_detour() {
+        let graph = |&n: &u8| match n {
+            0 => vec![1, 2],
+            1 => vec![3],
+            2 => vec![3],
+            _ => vec![],
+        };
+        let cost = |&from: &u8, &to: &u8| if (from, to) == (0, 1) { 10u32 } else { 1 };
+        let search = dijkstra([0], graph, cost, |&n| n == 3);
+        assert_eq!(search.cost(), Some(2));
+        assert_eq!(search.path(), Some(vec![0, 2, 3]));
+    }
+
+    #[test]
+    fn astar_expands_fewer_nodes_than_dijkstra() {
+        let goal = Point::new(10, 0);
+        let step = |_: &Point<i32>, _: &Point<i32>| 1;
+        let guided = astar(
+            [Point::new(0, 0)],
+            open(&[]),
+            step,
+            |p| p.manhattan(&goal),
+            |&p| p == goal,
+        );
+        let unguided = dijkstra([Point::new(0, 0)], open(&[]), step, |&p| p == goal);
+        assert_eq!(guided.cost(), Some(10));
+        assert_eq!(unguided.cost(), Some(10));
+        assert!(guided.stats.expanded < unguided.stats.expanded);
+    }
+}
@@ -0,0 +1,142 @@
+/// Tolerance for treating a floating point value as zero.
+pub const EPSILON: f64 = 1e-9;
+
+/// An optimal solution to a linear program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Optimum {
+    /// The optimal value of each variable
+    pub x: Vec<f64>,
+    /// The shadow price of each constraint, which solves the dual program
+    pub dual: Vec<f64>,
+    /// The objective at `x`
+    pub value: f64,
+}
+
+/// Maximises `c · x` subject to `a x <= b` and `x >= 0`, using the simplex
+/// method with Bland's rule so it cannot cycle.
+///
+/// Every entry of `b` must be non-negative, so that `x = 0` is feasible.
+/// Returns `None` if the objective is unbounded.
+pub fn maximise(c: &[f64], a: &[Vec<f64>], b: &[f64]) -> Option<Optimum> {
+    let (m, n) = (a.len(), c.len());
+    assert!(b.iter().all(|&b| b >= 0.0), "origin must be feasible");
+
+    // Each row is a constraint with a slack variable, then the bound; the
+    // last row holds the negated reduced costs and the objective.
+    let mut tableau = a
+        .iter()
+        .zip(b)
+        .enumerate()
+        .map(|(i, (row, &b))| {
+            let mut slack = vec![0.0; m];
+            slack[i] = 1.0;
+            [&row[..], &slack, &[b]].concat()
+        })
+        .collect::<Vec<_>>();
+    tableau.push([c.iter().map(|c| -c).collect(), vec![0.0; m + 1]].concat());
+    let mut basis = (n..n + m).collect::<Vec<_>>();
+
+    while let Some(entering) = (0..n + m).find(|&j| tableau[m][j] < -EPSILON) {
+        let leaving = (0..m)
+            .filter(|&i| tableau[i][entering] > EPSILON)
+            .min_by(|&i, &j| {
+                let ratio = |row: usize| tableau[row][n + m] / tableau[row][entering];
+                ratio(i).total_cmp(&ratio(j)).then(basis[i].cmp(&basis[j]))
+            })?;
+        pivot(&mut tableau, leaving, entering);
+        basis[leaving] = entering;
+    }
+
+    let mut x = vec![0.0; n];
+    for (i, &variable) in basis.iter().enumerate() {
+        if variable < n {
+            x[variable] = tableau[i][n + m];
+        }
+    }
+    Some(Optimum {
+        x,
+        dual: tableau[m][n..n + m].to_vec(),
+        value: tableau[m][n + m],
+    })
+}
+
+/// Makes `column` basic in `row`, eliminating it from every other row.
+fn pivot(tableau: &mut [Vec<f64>], row: usize, column: usize) {
+    let scale = tableau[row][column];
+    tableau[row].iter_mut().for_each(|value| *value /= scale);
+    let pivot = tableau[row].clone();
+    for (i, other) in tableau.iter_mut().enumerate() {
+        let factor = other[column];
+        if i != row && factor != 0.0 {
+            other
+                .iter_mut()
+                .zip(&pivot)
+                .for_each(|(value, p)| *value -= factor * p);
+        }
+    }
+}
+
+/// Solves the square system `a x = b` by Gaussian elimination with partial
+/// pivoting, returning `None` if `a` is singular.
+pub fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for column in 0..n {
+        let magnitude = |row: usize| a[row][column].abs();
+        let row = (column..n).max_by(|&i, &j| magnitude(i).total_cmp(&magnitude(j)))?;
+        if magnitude(row) < EPSILON {
+            return None;
+        }
+        a.swap(column, row);
+        b.swap(column, row);
+        let (upper, lower) = a.split_at_mut(column + 1);
+        let pivot = &upper[column];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[column] / pivot[column];
+            row.iter_mut()
+                .zip(pivot)
+                .skip(column)
+                .for_each(|(value, p)| *value -= factor * p);
+            b[column + 1 + offset] -= factor * b[column];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum = (i + 1..n).map(|j| a[i][j] * x[j]).sum::<f64>();
+        x[i] = (b[i] - sum) / a[i][i];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-6)
+    }
+
+    #[test]
+    fn maximises_with_duals() {
+        // max 3x + 5y, x <= 4, 2y <= 12, 3x + 2y <= 18
+        let a = vec![vec![1.0, 0.0], vec![0.0, 2.0], vec![3.0, 2.0]];
+        let optimum = maximise(&[3.0, 5.0], &a, &[4.0, 12.0, 18.0]).unwrap();
+        assert!(close(&optimum.x, &[2.0, 6.0]));
+        assert!((optimum.value - 36.0).abs() < 1e-6);
+        assert!(close(&optimum.dual, &[0.0, 1.5, 1.0]));
+    }
+
+    #[test]
+    fn detects_unbounded_programs() {
+        assert_eq!(maximise(&[1.0, 1.0], &[vec![1.0, -1.0]], &[1.0]), None);
+    }
+
+    #[test]
+    fn solves_linear_systems() {
+        let a = vec![vec![0.0, 2.0], vec![1.0, 1.0]];
+        assert!(close(&solve(a, vec![4.0, 3.0]).unwrap(), &[1.0, 2.0]));
+        assert_eq!(
+            solve(vec![vec![1.0, 2.0], vec![2.0, 4.0]], vec![1.0, 2.0]),
+            None
+        );
+    }
+}
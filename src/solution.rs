@@ -0,0 +1,272 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::error::ParseError;
+use crate::render::Frame;
+use crate::{
+    day_1, day_10, day_11, day_12, day_13, day_14, day_2, day_3, day_4, day_5, day_6, day_7, day_8,
+    day_9,
+};
+
+/// One of the two parts making up each day's challenge.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" | "one" => Ok(Part::One),
+            "2" | "two" => Ok(Part::Two),
+            other => Err(format!("Unknown part `{other}`, expected `1` or `2`")),
+        }
+    }
+}
+
+/// A type-erased answer, produced from the typed answer of a [`Solution`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+}
+
+impl Answer {
+    /// The name of the variant, for reports that record each answer's type.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Unsigned(_) => "unsigned",
+            Answer::Signed(_) => "signed",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(value) => write!(f, "{value}"),
+            Answer::Signed(value) => write!(f, "{value}"),
+            Answer::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Self {
+                    Answer::$variant(value as $target)
+                }
+            }
+        )+
+    };
+}
+
+answer_from!(Unsigned, u64, u16, u32, u64, usize);
+answer_from!(Signed, i64, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+/// An answer that may not exist, such as a marker missing from its input,
+/// which is shown as `none`.
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        value.map_or_else(|| Answer::Text("none".to_string()), Into::into)
+    }
+}
+
+/// The interface implemented by every day's solver.
+///
+/// Parsing is separated from solving so the parsed input can be shared
+/// between both parts of a challenge.
+pub trait Solution {
+    /// The day of the advent calendar this solution belongs to.
+    const DAY: u8;
+
+    /// The parsed representation of the puzzle input.
+    type Input;
+    type PartOne: Into<Answer>;
+    type PartTwo: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part_one(input: &Self::Input) -> Self::PartOne;
+
+    fn part_two(input: &Self::Input) -> Self::PartTwo;
+
+    /// Draws the puzzle's state, for days whose state is spatial.
+    fn render(_input: &Self::Input) -> Option<Frame> {
+        None
+    }
+}
+
+/// Time spent in each phase of solving a day.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timing {
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+}
+
+/// The answer to one part, with the time spent parsing and solving.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Object-safe view of a [`Solution`], so solvers can be stored in the registry.
+pub trait Solver {
+    fn day(&self) -> u8;
+
+    fn solve(&self, input: &str, part: Part) -> Result<Answer, ParseError>;
+
+    /// Parses `input` and solves `part`, timing both phases.
+    fn run(&self, input: &str, part: Part) -> Result<Run, ParseError>;
+
+    /// Parses `input` once and solves both parts, timing each phase separately.
+    fn time(&self, input: &str) -> Result<Timing, ParseError>;
+
+    /// Parses `input` and draws it, if the day can be drawn.
+    fn render(&self, input: &str) -> Result<Option<Frame>, ParseError>;
+}
+
+impl<S: Solution> Solver for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn solve(&self, input: &str, part: Part) -> Result<Answer, ParseError> {
+        self.run(input, part).map(|run| run.answer)
+    }
+
+    fn run(&self, input: &str, part: Part) -> Result<Run, ParseError> {
+        let start = Instant::now();
+        let input = S::parse(input)?;
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        let answer = match part {
+            Part::One => S::part_one(&input).into(),
+            Part::Two => S::part_two(&input).into(),
+        };
+        let solve = start.elapsed();
+
+        Ok(Run {
+            answer,
+            parse,
+            solve,
+        })
+    }
+
+    fn time(&self, input: &str) -> Result<Timing, ParseError> {
+        let start = Instant::now();
+        let input = black_box(S::parse(black_box(input))?);
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        black_box(S::part_one(&input));
+        let part_one = start.elapsed();
+
+        let start = Instant::now();
+        black_box(S::part_two(&input));
+        let part_two = start.elapsed();
+
+        Ok(Timing {
+            parse,
+            part_one,
+            part_two,
+        })
+    }
+
+    fn render(&self, input: &str) -> Result<Option<Frame>, ParseError> {
+        S::parse(input).map(|input| S::render(&input))
+    }
+}
+
+/// Location of a puzzle input bundled alongside a day's solution, e.g. `input` or `test`.
+pub fn input_path(day: u8, name: &str) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "src",
+        &format!("day_{day}"),
+        name,
+    ]
+    .iter()
+    .collect()
+}
+
+/// Every implemented day, keyed by day number.
+pub fn registry() -> BTreeMap<u8, &'static dyn Solver> {
+    let solvers: [&'static dyn Solver; 14] = [
+        &day_1::Day1,
+        &day_2::Day2,
+        &day_3::Day3,
+        &day_4::Day4,
+        &day_5::Day5,
+        &day_6::Day6,
+        &day_7::Day7,
+        &day_8::Day8,
+        &day_9::Day9,
+        &day_10::Day10,
+        &day_11::Day11,
+        &day_12::Day12,
+        &day_13::Day13,
+        &day_14::Day14,
+    ];
+    solvers
+        .into_iter()
+        .map(|solver| (solver.day(), solver))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_contains_every_day() {
+        let registry = registry();
+        assert_eq!(
+            registry.keys().copied().collect::<Vec<_>>(),
+            (1..=14).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn registered_solver_matches_direct_call() {
+        let input = include_str!("day_6/test1");
+        let answer = registry()[&6].solve(input, Part::Two);
+        assert_eq!(answer, Ok(Answer::Unsigned(19)));
+    }
+}
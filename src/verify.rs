@@ -0,0 +1,213 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::{input_path, registry, Answer, Part};
+
+const BUNDLED: &str = include_str!("answers.tsv");
+
+/// Longest answer shown in a table cell before it is truncated.
+const CELL_WIDTH: usize = 24;
+
+/// The answer expected from one part of a day for one of its bundled inputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expectation {
+    pub day: u8,
+    pub part: Part,
+    /// Name of the input file in the day's directory, e.g. `input` or `test`.
+    pub input: String,
+    pub answer: String,
+}
+
+/// A set of expected answers, read from a tab-separated manifest.
+///
+/// Each non-empty line that is not a `#` comment holds a day, part, input
+/// name and answer. Newlines in an answer are written as `\n`.
+#[derive(Clone, Debug, Default)]
+pub struct Manifest(Vec<Expectation>);
+
+impl Manifest {
+    /// The manifest of answers for the bundled inputs, `src/answers.tsv`.
+    pub fn bundled() -> Self {
+        BUNDLED
+            .parse()
+            .expect("The bundled manifest is well formed")
+    }
+
+    pub fn expectations(&self) -> &[Expectation] {
+        &self.0
+    }
+
+    pub fn expected(&self, day: u8, part: Part, input: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|e| e.day == day && e.part == part && e.input == input)
+            .map(|e| e.answer.as_str())
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| {
+                let error = |reason: &str| format!("Manifest line {}: {reason}", index + 1);
+                match line.split('\t').collect::<Vec<_>>()[..] {
+                    [day, part, input, answer] => Ok(Expectation {
+                        day: day.parse().map_err(|_| error("invalid day"))?,
+                        part: part.parse().map_err(|e: String| error(&e))?,
+                        input: input.to_string(),
+                        answer: unescape(answer),
+                    }),
+                    _ => Err(error("expected four tab-separated columns")),
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(Manifest)
+    }
+}
+
+/// Writes newlines as `\n`, so an answer fits on a single line.
+pub fn escape(answer: &str) -> String {
+    answer.replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    answer.replace("\\n", "\n")
+}
+
+/// The result of checking one expected answer against its solver.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub expectation: Expectation,
+    /// The answer produced, or why no answer could be produced.
+    pub actual: Result<Answer, String>,
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        matches!(&self.actual, Ok(answer) if answer.to_string() == self.expectation.answer)
+    }
+}
+
+/// Runs every registered solver against the expectations in `manifest`,
+/// optionally only those for a single day.
+pub fn verify(manifest: &Manifest, day: Option<u8>) -> Vec<Outcome> {
+    let registry = registry();
+    manifest
+        .expectations()
+        .iter()
+        .filter(|expectation| day.is_none_or(|day| day == expectation.day))
+        .map(|expectation| {
+            let path = input_path(expectation.day, &expectation.input);
+            let actual = registry
+                .get(&expectation.day)
+                .ok_or_else(|| format!("Day {} has not been solved", expectation.day))
+                .and_then(|solver| {
+                    let input = std::fs::read_to_string(&path)
+                        .map_err(|e| format!("Failed to read `{}`: {e}", path.display()))?;
+                    solver
+                        .solve(&input, expectation.part)
+                        .map_err(|e| e.to_string())
+                });
+            Outcome {
+                expectation: expectation.clone(),
+                actual,
+            }
+        })
+        .collect()
+}
+
+/// A pass/fail table of verification outcomes, with a summary line.
+pub struct Table<'a>(pub &'a [Outcome]);
+
+fn cell(text: &str) -> String {
+    let text = escape(text);
+    match text.char_indices().nth(CELL_WIDTH - 1) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text,
+    }
+}
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = CELL_WIDTH;
+        writeln!(
+            f,
+            "{:>3}  {:>4}  {:<8}  {:<6}  {:<width$}  Actual",
+            "Day", "Part", "Input", "Status", "Expected"
+        )?;
+        for outcome in self.0 {
+            let Expectation {
+                day,
+                part,
+                input,
+                answer,
+            } = &outcome.expectation;
+            let actual = match &outcome.actual {
+                Ok(actual) => actual.to_string(),
+                Err(e) => format!("error: {e}"),
+            };
+            let status = if outcome.passed() { "pass" } else { "FAIL" };
+            writeln!(
+                f,
+                "{day:>3}  {part:>4}  {input:<8}  {status:<6}  {:<width$}  {}",
+                cell(answer),
+                cell(&actual),
+            )?;
+        }
+        let passed = self.0.iter().filter(|outcome| outcome.passed()).count();
+        write!(f, "{passed}/{} passed", self.0.len())
+    }
+}
+
+/// Asserts that `actual` matches the bundled manifest's answer.
+#[cfg(test)]
+pub(crate) fn assert_answer(day: u8, part: Part, input: &str, actual: impl Into<Answer>) {
+    let manifest = Manifest::bundled();
+    let expected = manifest
+        .expected(day, part, input)
+        .unwrap_or_else(|| panic!("No expected answer for day {day} part {part} `{input}`"));
+    assert_eq!(actual.into().to_string(), expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_manifest_lines() {
+        let manifest = "# comment\n\n10\t2\ttest\t#.\\n.#\n"
+            .parse::<Manifest>()
+            .unwrap();
+        assert_eq!(manifest.expected(10, Part::Two, "test"), Some("#.\n.#"));
+        assert_eq!(manifest.expected(10, Part::One, "test"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let error = "1\t3\tinput\t42".parse::<Manifest>().unwrap_err();
+        assert!(error.starts_with("Manifest line 1"));
+    }
+
+    #[test]
+    fn bundled_expectations_have_solvers_and_inputs() {
+        let registry = registry();
+        for expectation in Manifest::bundled().expectations() {
+            assert!(registry.contains_key(&expectation.day));
+            assert!(input_path(expectation.day, &expectation.input).is_file());
+        }
+    }
+
+    #[test]
+    fn reports_failures() {
+        let manifest = "6\t1\ttest1\t8".parse::<Manifest>().unwrap();
+        let outcomes = verify(&manifest, None);
+        assert!(!outcomes[0].passed());
+        let table = Table(&outcomes).to_string();
+        assert!(table.contains("FAIL"));
+        assert!(table.ends_with("0/1 passed"));
+    }
+}